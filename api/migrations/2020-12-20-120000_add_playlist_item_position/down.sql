@@ -0,0 +1,2 @@
+ALTER TABLE playlist_item
+    DROP COLUMN position;
@@ -0,0 +1,8 @@
+ALTER TABLE playlist_item
+    ADD COLUMN position integer NOT NULL DEFAULT 0;
+
+UPDATE playlist_item
+SET position = ordered.position
+FROM (SELECT id, row_number() OVER (PARTITION BY playlist ORDER BY id) - 1 AS position
+      FROM playlist_item) AS ordered
+WHERE playlist_item.id = ordered.id;
@@ -0,0 +1,4 @@
+ALTER TABLE playlist_item
+    DROP CONSTRAINT playlist_item_playlist_fkey,
+    ADD CONSTRAINT playlist_item_playlist_fkey
+        FOREIGN KEY (playlist) REFERENCES playlist (id) ON DELETE CASCADE;
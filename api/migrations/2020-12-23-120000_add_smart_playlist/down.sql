@@ -0,0 +1,10 @@
+ALTER TABLE guild_stat
+    DROP COLUMN track,
+    DROP COLUMN uri,
+    DROP COLUMN length;
+
+ALTER TABLE playlist
+    DROP COLUMN smart,
+    DROP COLUMN smart_author,
+    DROP COLUMN smart_days,
+    DROP COLUMN smart_amount;
@@ -0,0 +1,10 @@
+ALTER TABLE guild_stat
+    ADD COLUMN track  text    NOT NULL DEFAULT '',
+    ADD COLUMN uri    text    NOT NULL DEFAULT '',
+    ADD COLUMN length integer NOT NULL DEFAULT 0;
+
+ALTER TABLE playlist
+    ADD COLUMN smart        boolean NOT NULL DEFAULT false,
+    ADD COLUMN smart_author bigint  NOT NULL DEFAULT 0,
+    ADD COLUMN smart_days   integer NOT NULL DEFAULT 0,
+    ADD COLUMN smart_amount integer NOT NULL DEFAULT 0;
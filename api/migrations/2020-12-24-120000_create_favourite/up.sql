@@ -0,0 +1,14 @@
+CREATE TABLE favourite
+(
+    id         bigint    NOT NULL PRIMARY KEY DEFAULT pseudo_encrypt(nextval('seq_serial')),
+    author     bigint    NOT NULL,
+    guild      bigint    NOT NULL,
+    track      text      NOT NULL,
+    title      text      NOT NULL,
+    uri        text      NOT NULL,
+    length     integer   NOT NULL,
+    created_at timestamp NOT NULL DEFAULT current_timestamp,
+    UNIQUE (author, track)
+);
+
+CREATE INDEX favourite_guild_idx ON favourite (guild);
@@ -0,0 +1,2 @@
+ALTER TABLE guild_stat
+    DROP COLUMN reason;
@@ -0,0 +1,2 @@
+ALTER TABLE guild_stat
+    ADD COLUMN reason text NOT NULL DEFAULT '';
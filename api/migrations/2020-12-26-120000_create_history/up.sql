@@ -0,0 +1,13 @@
+CREATE TABLE history
+(
+    id         bigint    NOT NULL PRIMARY KEY DEFAULT pseudo_encrypt(nextval('seq_serial')),
+    account    bigint    NOT NULL,
+    guild      bigint    NOT NULL,
+    track      text      NOT NULL,
+    title      text      NOT NULL,
+    uri        text      NOT NULL,
+    length     integer   NOT NULL,
+    created_at timestamp NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX history_account_idx ON history (account, created_at DESC);
@@ -0,0 +1 @@
+DROP TABLE guild_stat_daily;
@@ -0,0 +1,21 @@
+CREATE TABLE guild_stat_daily
+(
+    guild     bigint  NOT NULL,
+    day       date    NOT NULL,
+    key       text    NOT NULL,
+    track     text    NOT NULL,
+    title     text    NOT NULL,
+    uri       text    NOT NULL,
+    length    integer NOT NULL,
+    author    bigint  NOT NULL,
+    plays     bigint  NOT NULL,
+    duration  bigint  NOT NULL,
+    finished  bigint  NOT NULL,
+    skipped   bigint  NOT NULL,
+    stuck     bigint  NOT NULL,
+    exception bigint  NOT NULL,
+    ended     bigint  NOT NULL,
+    PRIMARY KEY (guild, day, key, author)
+);
+
+CREATE INDEX guild_stat_daily_day_idx ON guild_stat_daily (day);
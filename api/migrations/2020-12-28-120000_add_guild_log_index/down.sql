@@ -0,0 +1 @@
+DROP INDEX guild_log_guild_idx;
@@ -0,0 +1 @@
+CREATE INDEX guild_log_guild_idx ON guild_log (guild, created_at DESC, id DESC);
@@ -0,0 +1,8 @@
+ALTER TABLE guild_log ADD COLUMN action text NOT NULL DEFAULT '';
+
+UPDATE guild_log SET action = coalesce(payload ->> 'action', kind);
+
+ALTER TABLE guild_log
+    ALTER COLUMN action DROP DEFAULT,
+    DROP COLUMN kind,
+    DROP COLUMN payload;
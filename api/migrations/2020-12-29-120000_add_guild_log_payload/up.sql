@@ -0,0 +1,9 @@
+ALTER TABLE guild_log
+    ADD COLUMN kind    text  NOT NULL DEFAULT 'legacy',
+    ADD COLUMN payload jsonb NOT NULL DEFAULT '{}';
+
+UPDATE guild_log SET payload = jsonb_build_object('action', action);
+
+ALTER TABLE guild_log
+    ALTER COLUMN kind DROP DEFAULT,
+    DROP COLUMN action;
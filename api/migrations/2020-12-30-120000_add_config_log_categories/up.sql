@@ -0,0 +1 @@
+ALTER TABLE config ADD COLUMN log_categories text[] NOT NULL DEFAULT '{player,queue,playlist,settings}';
@@ -0,0 +1,12 @@
+CREATE TABLE guild_webhook
+(
+    guild             bigint    NOT NULL,
+    category          text      NOT NULL,
+    url               text      NOT NULL,
+    failures          integer   NOT NULL DEFAULT 0,
+    last_error        text      NOT NULL DEFAULT '',
+    last_error_at     timestamp,
+    last_delivered_at timestamp,
+    created_at        timestamp NOT NULL DEFAULT current_timestamp,
+    PRIMARY KEY (guild, category)
+);
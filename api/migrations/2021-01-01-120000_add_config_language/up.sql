@@ -0,0 +1 @@
+ALTER TABLE config ADD COLUMN language text NOT NULL DEFAULT 'en';
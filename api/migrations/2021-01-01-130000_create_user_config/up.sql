@@ -0,0 +1,5 @@
+CREATE TABLE user_config
+(
+    id       bigint NOT NULL PRIMARY KEY,
+    language text   NOT NULL DEFAULT ''
+);
@@ -0,0 +1 @@
+ALTER TABLE guild_log DROP COLUMN origin;
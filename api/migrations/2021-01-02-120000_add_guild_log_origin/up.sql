@@ -0,0 +1 @@
+ALTER TABLE guild_log ADD COLUMN origin text NOT NULL DEFAULT 'dashboard';
@@ -0,0 +1,14 @@
+CREATE TABLE user_token
+(
+    id           bigint    NOT NULL PRIMARY KEY DEFAULT pseudo_encrypt(nextval('seq_serial')),
+    author       bigint    NOT NULL,
+    name         text      NOT NULL,
+    hash         text      NOT NULL UNIQUE,
+    scopes       text[]    NOT NULL,
+    guild        bigint,
+    expires_at   timestamp,
+    last_used_at timestamp,
+    created_at   timestamp NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX user_token_author_idx ON user_token (author);
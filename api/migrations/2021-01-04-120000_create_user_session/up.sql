@@ -0,0 +1,14 @@
+CREATE TABLE user_session
+(
+    id               bigint    NOT NULL PRIMARY KEY DEFAULT pseudo_encrypt(nextval('seq_serial')),
+    author           bigint    NOT NULL,
+    token            text      NOT NULL,
+    refresh_token    text      NOT NULL,
+    token_expires_at timestamp NOT NULL,
+    device           text      NOT NULL,
+    expires_at       timestamp NOT NULL,
+    last_seen_at     timestamp NOT NULL DEFAULT current_timestamp,
+    created_at       timestamp NOT NULL DEFAULT current_timestamp
+);
+
+CREATE INDEX user_session_author_idx ON user_session (author);
@@ -0,0 +1,24 @@
+ALTER TABLE config ADD COLUMN guild_roles bigint[] NOT NULL DEFAULT '{}';
+ALTER TABLE config ADD COLUMN playlist_roles bigint[] NOT NULL DEFAULT '{}';
+ALTER TABLE config ADD COLUMN player_roles bigint[] NOT NULL DEFAULT '{}';
+ALTER TABLE config ADD COLUMN queue_roles bigint[] NOT NULL DEFAULT '{}';
+ALTER TABLE config ADD COLUMN track_roles bigint[] NOT NULL DEFAULT '{}';
+
+UPDATE config
+SET guild_roles    = ARRAY(SELECT target
+                           FROM guild_permission
+                           WHERE guild = config.id AND kind = 'role' AND 'manage_guild' = ANY (allow)),
+    playlist_roles = ARRAY(SELECT target
+                           FROM guild_permission
+                           WHERE guild = config.id AND kind = 'role' AND 'manage_playlist' = ANY (allow)),
+    player_roles   = ARRAY(SELECT target
+                           FROM guild_permission
+                           WHERE guild = config.id AND kind = 'role' AND 'skip' = ANY (allow)),
+    queue_roles    = ARRAY(SELECT target
+                           FROM guild_permission
+                           WHERE guild = config.id AND kind = 'role' AND 'clear_queue' = ANY (allow)),
+    track_roles    = ARRAY(SELECT target
+                           FROM guild_permission
+                           WHERE guild = config.id AND kind = 'role' AND 'add_track' = ANY (allow));
+
+DROP TABLE guild_permission;
@@ -0,0 +1,44 @@
+CREATE TABLE guild_permission
+(
+    guild  bigint NOT NULL,
+    kind   text   NOT NULL,
+    target bigint NOT NULL,
+    allow  text[] NOT NULL DEFAULT '{}',
+    deny   text[] NOT NULL DEFAULT '{}',
+    PRIMARY KEY (guild, kind, target)
+);
+
+INSERT INTO guild_permission (guild, kind, target, allow)
+SELECT config.id, 'role', roles.target, array_agg(DISTINCT permissions.name)
+FROM config
+         CROSS JOIN LATERAL (VALUES (config.guild_roles, ARRAY ['manage_guild']),
+                                    (config.playlist_roles, ARRAY ['manage_playlist']),
+                                    (config.track_roles, ARRAY ['add_track', 'load_playlist']),
+                                    (config.queue_roles,
+                                     ARRAY ['remove_track', 'move_track', 'clear_queue', 'shuffle']),
+                                    (config.player_roles,
+                                     ARRAY ['pause', 'skip', 'seek', 'volume', 'filters', 'loop',
+                                         'disconnect'])) AS grants (roles, permissions)
+         CROSS JOIN LATERAL unnest(grants.roles) AS roles (target)
+         CROSS JOIN LATERAL unnest(grants.permissions) AS permissions (name)
+GROUP BY config.id, roles.target;
+
+INSERT INTO guild_permission (guild, kind, target)
+SELECT id, 'role', id
+FROM config
+ON CONFLICT DO NOTHING;
+
+UPDATE guild_permission
+SET deny = ARRAY(SELECT unnest(ARRAY ['load_playlist', 'add_track', 'remove_track', 'move_track',
+                                   'clear_queue', 'shuffle', 'pause', 'skip', 'seek', 'volume',
+                                   'filters', 'loop', 'disconnect'])
+                 EXCEPT
+                 SELECT unnest(allow))
+WHERE kind = 'role'
+  AND target = guild;
+
+ALTER TABLE config DROP COLUMN guild_roles;
+ALTER TABLE config DROP COLUMN playlist_roles;
+ALTER TABLE config DROP COLUMN player_roles;
+ALTER TABLE config DROP COLUMN queue_roles;
+ALTER TABLE config DROP COLUMN track_roles;
@@ -0,0 +1,5 @@
+UPDATE playlist
+SET guild = 0
+WHERE guild IS NULL;
+
+ALTER TABLE playlist ALTER COLUMN guild SET NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE playlist ALTER COLUMN guild DROP NOT NULL;
+
+UPDATE playlist
+SET guild = NULL
+WHERE guild = 0;
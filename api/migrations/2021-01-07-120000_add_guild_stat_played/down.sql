@@ -0,0 +1,5 @@
+DROP VIEW guild_stat_source;
+DROP VIEW guild_stat_raw;
+
+ALTER TABLE guild_stat
+    DROP COLUMN played;
@@ -0,0 +1,57 @@
+ALTER TABLE guild_stat
+    ADD COLUMN played integer;
+
+CREATE VIEW guild_stat_raw AS
+SELECT guild,
+       created_at::date AS day,
+       coalesce(nullif(track, ''), title) AS key,
+       track,
+       title,
+       uri,
+       length,
+       author,
+       1::bigint AS plays,
+       coalesce(played, length)::bigint AS duration,
+       (reason = 'finished')::int::bigint AS finished,
+       (reason = 'skipped')::int::bigint AS skipped,
+       (reason = 'stuck')::int::bigint AS stuck,
+       (reason = 'exception')::int::bigint AS exception,
+       (reason <> '')::int::bigint AS ended
+FROM guild_stat;
+
+CREATE VIEW guild_stat_source AS
+SELECT guild,
+       day,
+       key,
+       track,
+       title,
+       uri,
+       length,
+       author,
+       plays,
+       duration,
+       finished,
+       skipped,
+       stuck,
+       exception,
+       ended
+FROM guild_stat_daily
+WHERE day < (SELECT max(day) - 1 FROM guild_stat_daily)
+UNION ALL
+SELECT guild,
+       day,
+       key,
+       track,
+       title,
+       uri,
+       length,
+       author,
+       plays,
+       duration,
+       finished,
+       skipped,
+       stuck,
+       exception,
+       ended
+FROM guild_stat_raw
+WHERE day >= coalesce((SELECT max(day) - 1 FROM guild_stat_daily), '-infinity'::date);
@@ -0,0 +1,2 @@
+ALTER TABLE playlist_item
+    DROP CONSTRAINT playlist_item_position_key;
@@ -0,0 +1,8 @@
+UPDATE playlist_item
+SET position = ordered.position
+FROM (SELECT id, row_number() OVER (PARTITION BY playlist ORDER BY position, id) - 1 AS position
+      FROM playlist_item) AS ordered
+WHERE playlist_item.id = ordered.id;
+
+ALTER TABLE playlist_item
+    ADD CONSTRAINT playlist_item_position_key UNIQUE (playlist, position) DEFERRABLE INITIALLY DEFERRED;
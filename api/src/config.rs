@@ -1,3 +1,4 @@
+use crate::constants::{LOG_RETENTION_DEFAULT, STAT_RETENTION_DEFAULT};
 use crate::routes::ApiResult;
 
 use lazy_static::lazy_static;
@@ -18,6 +19,9 @@ lazy_static! {
         api_port: get_env_as("API_PORT"),
         api_workers: get_env_as("API_WORKERS"),
         api_secret: get_env("API_SECRET"),
+        api_legacy_auth: get_env_or("API_LEGACY_AUTH", false),
+        bot_signing_secret: get_env("BOT_SIGNING_SECRET"),
+        session_keys: get_env("SESSION_KEYS"),
         postgres_host: get_env("POSTGRES_HOST"),
         postgres_port: get_env_as("POSTGRES_PORT"),
         postgres_user: get_env("POSTGRES_USER"),
@@ -30,6 +34,8 @@ lazy_static! {
         andesite_host: get_env("ANDESITE_HOST"),
         andesite_port: get_env_as("ANDESITE_PORT"),
         andesite_secret: get_env("ANDESITE_SECRET"),
+        stat_retention: get_env_or("STAT_RETENTION", STAT_RETENTION_DEFAULT),
+        log_retention: get_env_or("LOG_RETENTION", LOG_RETENTION_DEFAULT),
     };
 }
 
@@ -59,6 +65,9 @@ pub struct Config {
     pub api_port: u16,
     pub api_workers: u64,
     pub api_secret: String,
+    pub api_legacy_auth: bool,
+    pub bot_signing_secret: String,
+    pub session_keys: String,
     pub postgres_host: String,
     pub postgres_port: u16,
     pub postgres_user: String,
@@ -71,6 +80,8 @@ pub struct Config {
     pub andesite_host: String,
     pub andesite_port: u16,
     pub andesite_secret: String,
+    pub stat_retention: u32,
+    pub log_retention: u32,
 }
 
 fn get_env(name: &str) -> String {
@@ -87,6 +98,19 @@ where
         .unwrap_or_else(|_| panic!("Invalid environmental variable: {}", name))
 }
 
+fn get_env_or<T>(name: &str, default: T) -> T
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Debug,
+{
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => value
+            .parse::<T>()
+            .unwrap_or_else(|_| panic!("Invalid environmental variable: {}", name)),
+        _ => default,
+    }
+}
+
 pub fn get_api_address() -> ApiResult<SocketAddr> {
     let addr = SocketAddr::new(IpAddr::from_str(CONFIG.api_host.as_str())?, CONFIG.api_port);
 
@@ -3,7 +3,12 @@ pub const PLAYER_QUEUE: &str = "player.recv";
 pub const PLAYER_SEND_QUEUE: &str = "player.send";
 
 pub const CACHE_DUMP_INTERVAL: usize = 5000;
+pub const RETENTION_INTERVAL: usize = 600000;
+pub const STAT_RETENTION_DEFAULT: u32 = 365;
+pub const LOG_RETENTION_DEFAULT: u32 = 90;
 pub const PLAYER_RECONNECT_WAIT: usize = 2000;
+pub const WEBHOOK_RETRY_WAIT: usize = 1000;
+pub const WEBHOOK_RETRY_MAX: usize = 5;
 
 pub const BLACKLIST_REASON_MIN: usize = 1;
 pub const BLACKLIST_REASON_MAX: usize = 1000;
@@ -38,43 +43,100 @@ pub const GUILD_PREFIX_MIN: usize = 1;
 pub const GUILD_PREFIX_MAX: usize = 5;
 pub const GUILD_QUEUE_MIN: usize = 1;
 pub const GUILD_QUEUE_MAX: usize = 5000;
-pub const GUILD_ROLES_MAX: usize = 10;
+pub const GUILD_PERMISSION_MAX: usize = 100;
 pub const PLAYLIST_MAX: usize = 100;
+pub const PLAYLIST_ITEM_MAX: usize = 5000;
+pub const PLAYLIST_IMPORT_MAX: usize = 500;
+pub const PLAYLIST_IMPORT_CONCURRENCY: usize = 10;
 pub const PLAYLIST_NAME_MIN: usize = 1;
 pub const PLAYLIST_NAME_MAX: usize = 50;
+pub const USER_PLAYLIST_MAX: usize = 25;
+pub const USER_PLAYLIST_ITEM_MAX: usize = 1000;
+pub const SMART_PLAYLIST_DAYS_MAX: usize = 90;
+pub const SMART_PLAYLIST_AMOUNT_MAX: usize = 100;
+pub const FAVOURITE_MAX: usize = 1000;
+pub const USER_TOKEN_MAX: usize = 25;
+pub const USER_TOKEN_NAME_MIN: usize = 1;
+pub const USER_TOKEN_NAME_MAX: usize = 50;
+pub const USER_TOKEN_LENGTH: usize = 40;
+pub const USER_TOKEN_PREFIX: &str = "wyvor_";
+pub const WEBHOOK_TOKEN_MASK: usize = 8;
+pub const WEBHOOK_ERROR_MAX: usize = 1000;
+pub const WEBHOOK_URL_PATH: &str = "/api/webhooks/";
+pub const WEBHOOK_URL_HOSTS: [&str; 4] = [
+    "discord.com",
+    "discordapp.com",
+    "canary.discord.com",
+    "ptb.discord.com",
+];
+
+pub const EMBED_COLOR: usize = 0xFF4500;
 
 pub const COOKIE_NAME: &str = "session";
+pub const SESSION_MAX_AGE: i64 = 2592000;
+pub const SESSION_IDLE_MAX: i64 = 604800;
+pub const SESSION_SEEN_INTERVAL: i64 = 300;
+pub const SESSION_REFRESH_MARGIN: i64 = 3600;
+pub const SESSION_REFRESH_WAIT: usize = 250;
+pub const SESSION_REFRESH_RETRY_MAX: usize = 20;
+pub const SESSION_DEVICE_MAX: usize = 200;
+pub const SIGNATURE_WINDOW: i64 = 300;
+pub const SIGNATURE_NONCE_MAX: usize = 64;
+pub const PAYLOAD_MAX: usize = 32768;
 pub const CALLBACK_PATH: &str = "/callback";
 
 pub const FETCH_USERS_MAX: usize = 100;
 pub const FETCH_LOGS_MAX: usize = 100;
+pub const FETCH_LOGS_EXPORT_MAX: usize = 10000;
+pub const FETCH_PLAYLIST_ITEMS_MAX: usize = 100;
+pub const FETCH_HISTORY_MAX: usize = 100;
 pub const FETCH_STAT_DAYS: usize = 7;
+pub const FETCH_STAT_DAYS_MAX: usize = 90;
 pub const FETCH_STAT_TRACKS: usize = 5;
 pub const FETCH_STAT_USERS: usize = 5;
+pub const FETCH_STAT_LIKES: usize = 5;
+pub const FETCH_STAT_GUILDS: usize = 10;
+pub const FETCH_STAT_RANGE_MAX: usize = 366;
 
 pub const BLACKLIST_KEY: &str = "blacklists";
 pub const BOT_ADMIN_KEY: &str = "bot_admins";
+pub const BOT_NONCE_KEY: &str = "bot_nonce";
 pub const BOT_OWNER_KEY: &str = "bot_owners";
 pub const CSRF_TOKEN_KEY: &str = "csrf_token";
 pub const GUILD_KEY: &str = "guild";
 pub const GUILD_CONFIG_KEY: &str = "guild_config";
+pub const GUILD_PERMISSION_KEY: &str = "guild_permission";
+pub const GUILD_PERMISSION_VERSION_KEY: &str = "guild_permission_version";
 pub const GUILD_PREFIX_KEY: &str = "guild_prefix";
 pub const PLAYER_KEY: &str = "player";
 pub const PLAYER_STATS_KEY: &str = "player_stats";
 pub const QUEUE_KEY: &str = "queue";
 pub const QUEUE_LOOP_KEY: &str = "queue_loop";
 pub const QUEUE_PLAYING_KEY: &str = "queue_playing";
+pub const SESSION_REFRESH_KEY: &str = "session_refresh";
 pub const STATS_KEY: &str = "bot_stats";
 pub const STATUS_KEY: &str = "gateway_statuses";
 pub const USER_KEY: &str = "user";
+pub const USER_CONFIG_KEY: &str = "user_config";
 pub const USER_GUILDS_KEY: &str = "user_guilds";
+pub const USER_SESSION_KEY: &str = "user_session";
 pub const USER_TOKEN_KEY: &str = "user_token";
+pub const WEBHOOK_RATELIMIT_KEY: &str = "webhook_ratelimit";
 
+pub const BOT_NONCE_KEY_TTL: usize = 600000;
 pub const CSRF_TOKEN_KEY_TTL: usize = 300000;
+pub const GUILD_PERMISSION_KEY_TTL: usize = 10000;
+pub const SESSION_REFRESH_KEY_TTL: usize = 10000;
 pub const USER_KEY_TTL: usize = 60000;
+pub const USER_CONFIG_KEY_TTL: usize = 60000;
 pub const USER_GUILDS_KEY_TTL: usize = 5000;
+pub const USER_SESSION_KEY_TTL: usize = 60000;
 pub const USER_TOKEN_KEY_TTL: usize = 60000;
 
+pub fn bot_nonce_key(id: &str) -> String {
+    format!("{}:{}", BOT_NONCE_KEY, id)
+}
+
 pub fn csrf_token_key(id: &str) -> String {
     format!("{}:{}", CSRF_TOKEN_KEY, id)
 }
@@ -87,6 +149,14 @@ pub fn guild_config_key(id: u64) -> String {
     format!("{}:{}", GUILD_CONFIG_KEY, id)
 }
 
+pub fn guild_permission_key(id: u64, version: u64, user: u64) -> String {
+    format!("{}:{}:{}:{}", GUILD_PERMISSION_KEY, id, version, user)
+}
+
+pub fn guild_permission_version_key(id: u64) -> String {
+    format!("{}:{}", GUILD_PERMISSION_VERSION_KEY, id)
+}
+
 pub fn guild_prefix_key(id: u64) -> String {
     format!("{}:{}", GUILD_PREFIX_KEY, id)
 }
@@ -107,14 +177,30 @@ pub fn queue_playing_key(id: u64) -> String {
     format!("{}:{}", QUEUE_PLAYING_KEY, id)
 }
 
+pub fn session_refresh_key(id: u64) -> String {
+    format!("{}:{}", SESSION_REFRESH_KEY, id)
+}
+
 pub fn user_key(id: u64) -> String {
     format!("{}:{}", USER_KEY, id)
 }
 
+pub fn user_config_key(id: u64) -> String {
+    format!("{}:{}", USER_CONFIG_KEY, id)
+}
+
 pub fn user_guilds_key(id: u64) -> String {
     format!("{}:{}", USER_GUILDS_KEY, id)
 }
 
+pub fn user_session_key(id: u64) -> String {
+    format!("{}:{}", USER_SESSION_KEY, id)
+}
+
 pub fn user_token_key(id: &str) -> String {
     format!("{}:{}", USER_TOKEN_KEY, id)
 }
+
+pub fn webhook_ratelimit_key(guild: u64, category: &str) -> String {
+    format!("{}:{}:{}", WEBHOOK_RATELIMIT_KEY, guild, category)
+}
@@ -1,11 +1,15 @@
 use crate::constants::{
-    guild_config_key, guild_prefix_key, BLACKLIST_KEY, CACHE_DUMP_INTERVAL, GUILD_KEY, USER_KEY,
+    guild_config_key, guild_permission_key, guild_permission_version_key, guild_prefix_key,
+    user_config_key, user_session_key, BLACKLIST_KEY, CACHE_DUMP_INTERVAL, GUILD_KEY,
+    USER_CONFIG_KEY_TTL, USER_KEY, USER_SESSION_KEY_TTL,
 };
 use crate::db::{PgPool, RedisPool};
 use crate::models::account::{self, Account};
 use crate::models::blacklist;
-use crate::models::config::{self, Config, EditConfig, NewConfig};
+use crate::models::config::{self, Config, NewConfig};
 use crate::models::guild::{self, NewGuild};
+use crate::models::user_config::{self, UserConfig};
+use crate::models::user_session::{self, UserSession};
 use crate::routes::{ApiResult, OptionExt};
 use crate::utils::sleep;
 
@@ -138,6 +142,26 @@ pub async fn del(pool: &RedisPool, key: impl ToString) -> ApiResult<()> {
     Ok(())
 }
 
+pub async fn set_nx_and_expire<T: Serialize>(
+    pool: &RedisPool,
+    key: impl ToString,
+    value: &T,
+    expiry: usize,
+) -> ApiResult<bool> {
+    let pool = pool.clone();
+    let mut conn = block(move || pool.get()).await?;
+    let res: Option<String> = redis::cmd("SET")
+        .arg(key.to_string())
+        .arg(serde_json::to_string(value)?)
+        .arg("NX")
+        .arg("PX")
+        .arg(expiry)
+        .query_async(&mut *conn)
+        .await?;
+
+    Ok(res.is_some())
+}
+
 pub async fn sadd(pool: &RedisPool, key: impl ToString, value: impl ToString) -> ApiResult<()> {
     let pool = pool.clone();
     let mut conn = block(move || pool.get()).await?;
@@ -165,26 +189,6 @@ pub async fn get_config(pool: &PgPool, redis_pool: &RedisPool, guild: u64) -> Ap
         Ok(config)
     } else {
         config::create(pool, NewConfig { id: guild as i64 }).await?;
-        config::update(
-            pool,
-            guild as i64,
-            EditConfig {
-                prefix: None,
-                max_queue: None,
-                no_duplicate: None,
-                keep_alive: None,
-                guild_roles: None,
-                playlist_roles: None,
-                player_roles: Some(vec![guild as i64]),
-                queue_roles: Some(vec![guild as i64]),
-                track_roles: Some(vec![guild as i64]),
-                playing_log: None,
-                player_log: None,
-                queue_log: None,
-            },
-        )
-        .await?;
-
         invalidate_config(pool, redis_pool, guild).await?;
 
         let config = config::find(pool, guild as i64).await?.or_not_found()?;
@@ -193,6 +197,35 @@ pub async fn get_config(pool: &PgPool, redis_pool: &RedisPool, guild: u64) -> Ap
     }
 }
 
+pub async fn get_user_config(
+    pool: &PgPool,
+    redis_pool: &RedisPool,
+    user: u64,
+) -> ApiResult<UserConfig> {
+    let user_config: Option<UserConfig> = get(redis_pool, user_config_key(user)).await?;
+
+    if let Some(user_config) = user_config {
+        Ok(user_config)
+    } else {
+        let user_config = user_config::find(pool, user as i64)
+            .await?
+            .unwrap_or(UserConfig {
+                id: user as i64,
+                ..Default::default()
+            });
+
+        set_and_expire(
+            redis_pool,
+            user_config_key(user),
+            &user_config,
+            USER_CONFIG_KEY_TTL,
+        )
+        .await?;
+
+        Ok(user_config)
+    }
+}
+
 pub async fn get_blacklist_item(pool: &RedisPool, user: u64) -> ApiResult<Option<()>> {
     let blacklist = sismember(pool, BLACKLIST_KEY, &user).await?;
 
@@ -212,6 +245,66 @@ pub async fn invalidate_config(pool: &PgPool, redis_pool: &RedisPool, guild: u64
     Ok(())
 }
 
+pub async fn get_guild_permission_key(
+    redis_pool: &RedisPool,
+    guild: u64,
+    user: u64,
+) -> ApiResult<String> {
+    let version: Option<u64> = get(redis_pool, guild_permission_version_key(guild)).await?;
+
+    Ok(guild_permission_key(
+        guild,
+        version.unwrap_or_default(),
+        user,
+    ))
+}
+
+pub async fn invalidate_guild_permissions(redis_pool: &RedisPool, guild: u64) -> ApiResult<()> {
+    let redis_pool = redis_pool.clone();
+    let mut conn = block(move || redis_pool.get()).await?;
+    let _: u64 = conn.incr(guild_permission_version_key(guild), 1).await?;
+
+    Ok(())
+}
+
+pub async fn get_user_session(
+    pool: &PgPool,
+    redis_pool: &RedisPool,
+    id: u64,
+) -> ApiResult<Option<UserSession>> {
+    let user_session: Option<UserSession> = get(redis_pool, user_session_key(id)).await?;
+
+    if user_session.is_some() {
+        return Ok(user_session);
+    }
+
+    let user_session = user_session::find(pool, id as i64).await?;
+
+    if let Some(user_session) = &user_session {
+        set_and_expire(
+            redis_pool,
+            user_session_key(id),
+            user_session,
+            USER_SESSION_KEY_TTL,
+        )
+        .await?;
+    }
+
+    Ok(user_session)
+}
+
+pub async fn invalidate_user_config(redis_pool: &RedisPool, user: u64) -> ApiResult<()> {
+    del(redis_pool, user_config_key(user)).await?;
+
+    Ok(())
+}
+
+pub async fn invalidate_user_session(redis_pool: &RedisPool, id: u64) -> ApiResult<()> {
+    del(redis_pool, user_session_key(id)).await?;
+
+    Ok(())
+}
+
 pub async fn invalidate_blacklist(pool: &PgPool, redis_pool: &RedisPool) -> ApiResult<()> {
     load_blacklist(pool, redis_pool).await?;
 
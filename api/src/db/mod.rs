@@ -13,6 +13,7 @@ use tokio::runtime::Runtime;
 pub mod cache;
 pub mod migration;
 pub mod pubsub;
+pub mod retention;
 pub mod schema;
 
 lazy_static! {
@@ -125,6 +125,7 @@ impl Message {
         channel: u64,
         title: &str,
         content: &str,
+        footer: &str,
         author: impl Into<Option<u64>>,
     ) -> Message {
         Message::new(
@@ -133,6 +134,7 @@ impl Message {
                 "channel": channel,
                 "title": title,
                 "content": content,
+                "footer": footer,
                 "author": author.into()
             }),
         )
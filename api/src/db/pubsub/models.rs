@@ -50,6 +50,8 @@ pub struct Permission {
 pub struct Connected {
     pub channel: i64,
     pub members: Vec<i64>,
+    #[serde(default)]
+    pub bots: Vec<i64>,
 }
 
 #[derive(Debug, Deserialize)]
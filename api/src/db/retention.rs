@@ -0,0 +1,41 @@
+use crate::config::CONFIG;
+use crate::constants::{FETCH_STAT_DAYS, RETENTION_INTERVAL};
+use crate::db::PgPool;
+use crate::models::{guild_log, guild_stat};
+use crate::routes::ApiResult;
+use crate::utils::sleep;
+
+use std::time::Duration;
+use tracing::warn;
+
+pub fn init_retention(pool: PgPool) {
+    actix_web::rt::spawn(async move {
+        loop {
+            let err = run_jobs(&pool).await;
+            warn!("Retention jobs ended unexpectedly: {:?}", err);
+            sleep(Duration::from_millis(RETENTION_INTERVAL as u64))
+                .await
+                .ok();
+        }
+    });
+}
+
+async fn run_jobs(pool: &PgPool) -> ApiResult<()> {
+    loop {
+        guild_stat::roll_up(pool).await?;
+
+        if CONFIG.stat_retention > 0 {
+            guild_stat::delete_expired(
+                pool,
+                CONFIG.stat_retention.max(FETCH_STAT_DAYS as u32) as i32,
+            )
+            .await?;
+        }
+
+        if CONFIG.log_retention > 0 {
+            guild_log::delete_expired(pool, CONFIG.log_retention as i32).await?;
+        }
+
+        sleep(Duration::from_millis(RETENTION_INTERVAL as u64)).await?;
+    }
+}
@@ -23,14 +23,24 @@ table! {
         max_queue -> Int4,
         no_duplicate -> Bool,
         keep_alive -> Bool,
-        guild_roles -> Array<Int8>,
-        playlist_roles -> Array<Int8>,
-        player_roles -> Array<Int8>,
-        queue_roles -> Array<Int8>,
-        track_roles -> Array<Int8>,
         playing_log -> Int8,
         player_log -> Int8,
         queue_log -> Int8,
+        log_categories -> Array<Text>,
+        language -> Text,
+    }
+}
+
+table! {
+    favourite (id) {
+        id -> Int8,
+        author -> Int8,
+        guild -> Int8,
+        track -> Text,
+        title -> Text,
+        uri -> Text,
+        length -> Int4,
+        created_at -> Timestamp,
     }
 }
 
@@ -50,9 +60,21 @@ table! {
     guild_log (id) {
         id -> Int8,
         guild -> Int8,
-        action -> Text,
         author -> Int8,
         created_at -> Timestamp,
+        kind -> Text,
+        payload -> Jsonb,
+        origin -> Text,
+    }
+}
+
+table! {
+    guild_permission (guild, kind, target) {
+        guild -> Int8,
+        kind -> Text,
+        target -> Int8,
+        allow -> Array<Text>,
+        deny -> Array<Text>,
     }
 }
 
@@ -63,16 +85,71 @@ table! {
         author -> Int8,
         title -> Text,
         created_at -> Timestamp,
+        track -> Text,
+        uri -> Text,
+        length -> Int4,
+        reason -> Text,
+        played -> Nullable<Int4>,
     }
 }
 
 table! {
-    playlist (id) {
+    guild_stat_daily (guild, day, key, author) {
+        guild -> Int8,
+        day -> Date,
+        key -> Text,
+        track -> Text,
+        title -> Text,
+        uri -> Text,
+        length -> Int4,
+        author -> Int8,
+        plays -> Int8,
+        duration -> Int8,
+        finished -> Int8,
+        skipped -> Int8,
+        stuck -> Int8,
+        exception -> Int8,
+        ended -> Int8,
+    }
+}
+
+table! {
+    guild_webhook (guild, category) {
+        guild -> Int8,
+        category -> Text,
+        url -> Text,
+        failures -> Int4,
+        last_error -> Text,
+        last_error_at -> Nullable<Timestamp>,
+        last_delivered_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    history (id) {
         id -> Int8,
+        account -> Int8,
         guild -> Int8,
+        track -> Text,
+        title -> Text,
+        uri -> Text,
+        length -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    playlist (id) {
+        id -> Int8,
+        guild -> Nullable<Int8>,
         name -> Text,
         author -> Int8,
         created_at -> Timestamp,
+        smart -> Bool,
+        smart_author -> Int8,
+        smart_days -> Int4,
+        smart_amount -> Int4,
     }
 }
 
@@ -84,18 +161,62 @@ table! {
         title -> Text,
         uri -> Text,
         length -> Int4,
+        position -> Int4,
     }
 }
 
 joinable!(playlist_item -> playlist (playlist));
 
+table! {
+    user_config (id) {
+        id -> Int8,
+        language -> Text,
+    }
+}
+
+table! {
+    user_session (id) {
+        id -> Int8,
+        author -> Int8,
+        token -> Text,
+        refresh_token -> Text,
+        token_expires_at -> Timestamp,
+        device -> Text,
+        expires_at -> Timestamp,
+        last_seen_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+table! {
+    user_token (id) {
+        id -> Int8,
+        author -> Int8,
+        name -> Text,
+        hash -> Text,
+        scopes -> Array<Text>,
+        guild -> Nullable<Int8>,
+        expires_at -> Nullable<Timestamp>,
+        last_used_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
 allow_tables_to_appear_in_same_query!(
     account,
     blacklist,
     config,
+    favourite,
     guild,
     guild_log,
+    guild_permission,
     guild_stat,
+    guild_stat_daily,
+    guild_webhook,
+    history,
     playlist,
     playlist_item,
+    user_config,
+    user_session,
+    user_token,
 );
@@ -10,10 +10,13 @@ use crate::config::{get_api_address, Environment, CONFIG};
 use crate::db::cache::init_cache;
 use crate::db::migration::run_migrations;
 use crate::db::pubsub::init_pubsub;
+use crate::db::retention::init_retention;
 use crate::db::{get_amqp_conn, get_pg_pool, get_redis_pool};
 use crate::routes::{admin, errors, guilds, index, tracks, users, ApiResult};
+use crate::utils::auth::init_session_keys;
 use crate::utils::metrics::Metrics;
 use crate::utils::player::init_player;
+use crate::utils::signature::Signature;
 
 use actix_web::http::StatusCode;
 use actix_web::middleware::errhandlers::ErrorHandlers;
@@ -57,7 +60,9 @@ pub async fn real_main() -> ApiResult<()> {
 
     run_migrations(&pool).await?;
 
+    init_session_keys();
     init_cache(pool.clone(), redis_pool.clone());
+    init_retention(pool.clone());
     init_pubsub();
     init_player(pool.clone(), redis_pool.clone(), amqp_channel.clone());
 
@@ -72,12 +77,14 @@ pub async fn real_main() -> ApiResult<()> {
                     .handler(StatusCode::FORBIDDEN, errors::forbidden)
                     .handler(StatusCode::NOT_FOUND, errors::not_found)
                     .handler(StatusCode::REQUEST_TIMEOUT, errors::request_timeout)
+                    .handler(StatusCode::PAYLOAD_TOO_LARGE, errors::payload_too_large)
                     .handler(
                         StatusCode::INTERNAL_SERVER_ERROR,
                         errors::internal_server_error,
                     )
                     .handler(StatusCode::SERVICE_UNAVAILABLE, errors::service_unavailable),
             )
+            .wrap(Signature)
             .wrap(Metrics)
             .wrap(Logger::default())
             .wrap(NormalizePath::new(TrailingSlash::Trim))
@@ -95,6 +102,7 @@ pub async fn real_main() -> ApiResult<()> {
                         web::scope("/admin")
                             .service(admin::get_guilds)
                             .service(admin::get_top_guilds)
+                            .service(admin::get_stats)
                             .service(admin::get_blacklist)
                             .service(admin::put_blacklist_item)
                             .service(admin::patch_blacklist_item)
@@ -111,20 +119,37 @@ pub async fn real_main() -> ApiResult<()> {
                             .service(guilds::post_guild_player)
                             .service(guilds::patch_guild_player)
                             .service(guilds::delete_guild_player)
+                            .service(guilds::post_guild_player_like)
                             .service(guilds::get_guild_queue)
                             .service(guilds::post_guild_queue)
                             .service(guilds::delete_guild_queue)
                             .service(guilds::post_guild_queue_shuffle)
+                            .service(guilds::post_guild_queue_favourites)
                             .service(guilds::put_guild_queue_item_position)
                             .service(guilds::delete_guild_queue_item)
                             .service(guilds::get_guild_playlists)
                             .service(guilds::post_guild_playlists)
+                            .service(guilds::post_guild_playlists_import)
+                            .service(guilds::post_guild_playlists_smart)
                             .service(guilds::patch_guild_playlist)
                             .service(guilds::delete_guild_playlist)
                             .service(guilds::post_guild_playlist_load)
+                            .service(guilds::get_guild_playlist_items)
+                            .service(guilds::post_guild_playlist_items)
+                            .service(guilds::post_guild_playlist_items_playing)
+                            .service(guilds::put_guild_playlist_item_position)
+                            .service(guilds::delete_guild_playlist_item)
+                            .service(guilds::get_guild_playlist_export)
                             .service(guilds::get_guild_settings)
                             .service(guilds::patch_guild_settings)
-                            .service(guilds::get_guild_logs),
+                            .service(guilds::get_guild_logs)
+                            .service(guilds::get_guild_logs_export)
+                            .service(guilds::get_guild_webhooks)
+                            .service(guilds::put_guild_webhook)
+                            .service(guilds::delete_guild_webhook)
+                            .service(guilds::get_guild_permissions)
+                            .service(guilds::put_guild_permission)
+                            .service(guilds::delete_guild_permission),
                     )
                     .service(
                         web::scope("/tracks")
@@ -139,6 +164,26 @@ pub async fn real_main() -> ApiResult<()> {
                             .service(users::get_user_me)
                             .service(users::get_user_me_guilds)
                             .service(users::get_users_me_guild)
+                            .service(users::get_user_me_playlists)
+                            .service(users::post_user_me_playlists)
+                            .service(users::patch_user_me_playlist)
+                            .service(users::delete_user_me_playlist)
+                            .service(users::get_user_me_playlist_items)
+                            .service(users::post_user_me_playlist_items)
+                            .service(users::put_user_me_playlist_item_position)
+                            .service(users::delete_user_me_playlist_item)
+                            .service(users::get_user_me_favourites)
+                            .service(users::delete_user_me_favourite)
+                            .service(users::get_user_me_history)
+                            .service(users::get_user_me_settings)
+                            .service(users::patch_user_me_settings)
+                            .service(users::get_user_me_sessions)
+                            .service(users::delete_user_me_session)
+                            .service(users::get_user_me_tokens)
+                            .service(users::post_user_me_tokens)
+                            .service(users::delete_user_me_token)
+                            .service(users::get_user_me_data)
+                            .service(users::delete_user_me_data)
                             .service(users::post_user_me_logout),
                     ),
             )
@@ -40,7 +40,7 @@ impl Validate for NewBlacklist {
         self.reason.len().check_btw(
             BLACKLIST_REASON_MIN,
             BLACKLIST_REASON_MAX,
-            "length of reason",
+            "field.reason_length",
         )?;
         Ok(())
     }
@@ -52,7 +52,7 @@ impl Validate for EditBlacklist {
             reason.len().check_btw(
                 BLACKLIST_REASON_MIN,
                 BLACKLIST_REASON_MAX,
-                "length of reason",
+                "field.reason_length",
             )?;
         }
         Ok(())
@@ -1,10 +1,10 @@
-use crate::constants::{
-    GUILD_PREFIX_MAX, GUILD_PREFIX_MIN, GUILD_QUEUE_MAX, GUILD_QUEUE_MIN, GUILD_ROLES_MAX,
-};
+use crate::constants::{GUILD_PREFIX_MAX, GUILD_PREFIX_MIN, GUILD_QUEUE_MAX, GUILD_QUEUE_MIN};
 use crate::db::schema::config;
 use crate::db::PgPool;
-use crate::models::{check_duplicate, string_int_opt, string_int_opt_vec, Validate, ValidateExt};
-use crate::routes::ApiResult;
+use crate::models::guild_log::LogCategory;
+use crate::models::{check_duplicate, string_int_opt, Validate, ValidateExt};
+use crate::routes::{ApiResponse, ApiResult};
+use crate::utils::locale;
 
 use actix_web::web::block;
 use diesel::prelude::*;
@@ -19,14 +19,11 @@ pub struct Config {
     pub max_queue: i32,
     pub no_duplicate: bool,
     pub keep_alive: bool,
-    pub guild_roles: Vec<i64>,
-    pub playlist_roles: Vec<i64>,
-    pub player_roles: Vec<i64>,
-    pub queue_roles: Vec<i64>,
-    pub track_roles: Vec<i64>,
     pub playing_log: i64,
     pub player_log: i64,
     pub queue_log: i64,
+    pub log_categories: Vec<String>,
+    pub language: String,
 }
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -42,22 +39,14 @@ pub struct EditConfig {
     pub max_queue: Option<i32>,
     pub no_duplicate: Option<bool>,
     pub keep_alive: Option<bool>,
-    #[serde(default, deserialize_with = "string_int_opt_vec")]
-    pub guild_roles: Option<Vec<i64>>,
-    #[serde(default, deserialize_with = "string_int_opt_vec")]
-    pub playlist_roles: Option<Vec<i64>>,
-    #[serde(default, deserialize_with = "string_int_opt_vec")]
-    pub player_roles: Option<Vec<i64>>,
-    #[serde(default, deserialize_with = "string_int_opt_vec")]
-    pub queue_roles: Option<Vec<i64>>,
-    #[serde(default, deserialize_with = "string_int_opt_vec")]
-    pub track_roles: Option<Vec<i64>>,
     #[serde(default, deserialize_with = "string_int_opt")]
     pub playing_log: Option<i64>,
     #[serde(default, deserialize_with = "string_int_opt")]
     pub player_log: Option<i64>,
     #[serde(default, deserialize_with = "string_int_opt")]
     pub queue_log: Option<i64>,
+    pub log_categories: Option<Vec<String>>,
+    pub language: Option<String>,
 }
 
 impl Validate for EditConfig {
@@ -65,55 +54,36 @@ impl Validate for EditConfig {
         if let Some(prefix) = &self.prefix {
             prefix
                 .len()
-                .check_btw(GUILD_PREFIX_MIN, GUILD_PREFIX_MAX, "length of prefix")?;
+                .check_btw(GUILD_PREFIX_MIN, GUILD_PREFIX_MAX, "field.prefix_length")?;
         }
 
         if let Some(max_queue) = &self.max_queue {
             (max_queue.to_owned() as usize).check_btw(
                 GUILD_QUEUE_MIN,
                 GUILD_QUEUE_MAX,
-                "max queue",
+                "field.max_queue",
             )?;
         }
 
-        if let Some(guild_roles) = &self.guild_roles {
-            guild_roles
-                .len()
-                .check_max(GUILD_ROLES_MAX, "length of manage server roles")?;
-
-            check_duplicate(guild_roles.as_slice(), "manage server roles")?;
-        }
-
-        if let Some(playlist_roles) = &self.playlist_roles {
-            playlist_roles
-                .len()
-                .check_max(GUILD_ROLES_MAX, "length of manage playlist roles")?;
-
-            check_duplicate(playlist_roles.as_slice(), "manage playlist roles")?;
-        }
-
-        if let Some(player_roles) = &self.player_roles {
-            player_roles
-                .len()
-                .check_max(GUILD_ROLES_MAX, "length of manage player roles")?;
-
-            check_duplicate(player_roles.as_slice(), "manage player roles")?;
-        }
-
-        if let Some(queue_roles) = &self.queue_roles {
-            queue_roles
-                .len()
-                .check_max(GUILD_ROLES_MAX, "length of manage queue roles")?;
-
-            check_duplicate(queue_roles.as_slice(), "manage queue roles")?;
+        if let Some(log_categories) = &self.log_categories {
+            if log_categories
+                .iter()
+                .any(|category| category.parse::<LogCategory>().is_err())
+            {
+                return Err(ApiResponse::bad_request()
+                    .message("error.log_categories_invalid")
+                    .into());
+            }
+
+            check_duplicate(log_categories.as_slice(), "field.log_categories")?;
         }
 
-        if let Some(track_roles) = &self.track_roles {
-            track_roles
-                .len()
-                .check_max(GUILD_ROLES_MAX, "length of add track roles")?;
-
-            check_duplicate(track_roles.as_slice(), "add track roles")?;
+        if let Some(language) = &self.language {
+            if !locale::is_supported(language.as_str()) {
+                return Err(ApiResponse::bad_request()
+                    .message("error.language_invalid")
+                    .into());
+            }
         }
 
         Ok(())
@@ -0,0 +1,143 @@
+use crate::db::schema::favourite;
+use crate::db::PgPool;
+use crate::routes::ApiResult;
+
+use actix_web::web::block;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Text};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Queryable, Identifiable)]
+#[table_name = "favourite"]
+pub struct Favourite {
+    pub id: i64,
+    pub author: i64,
+    pub guild: i64,
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "favourite"]
+pub struct NewFavourite {
+    pub author: i64,
+    pub guild: i64,
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: i32,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct LikeStat {
+    #[sql_type = "Text"]
+    pub title: String,
+    #[sql_type = "BigInt"]
+    pub likes: i64,
+}
+
+pub async fn create(pool: &PgPool, new_favourite: NewFavourite) -> ApiResult<Option<Favourite>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<Favourite>> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(favourite::table)
+            .values(new_favourite)
+            .on_conflict((favourite::author, favourite::track))
+            .do_nothing()
+            .get_result(&*conn)
+            .optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, id: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(favourite::table.find(id)).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_author(pool: &PgPool, author: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(favourite::table.filter(favourite::author.eq(author)))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(pool: &PgPool, id: i64) -> ApiResult<Option<Favourite>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<Favourite>> {
+        let conn = pool.get()?;
+        let res = favourite::table.find(id).first(&*conn).optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_author(pool: &PgPool, author: i64) -> ApiResult<Vec<Favourite>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<Favourite>> {
+        let conn = pool.get()?;
+        let res = favourite::table
+            .filter(favourite::author.eq(author))
+            .order(favourite::created_at.desc())
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn count_by_author(pool: &PgPool, author: i64) -> ApiResult<i64> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<i64> {
+        let conn = pool.get()?;
+        let res = favourite::table
+            .filter(favourite::author.eq(author))
+            .count()
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_most_liked(pool: &PgPool, guild: i64, amount: i64) -> ApiResult<Vec<LikeStat>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<LikeStat>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT min(title) AS title, count(*) AS likes FROM favourite WHERE guild = $1 GROUP \
+             BY track ORDER BY likes DESC, max(created_at) DESC LIMIT $2",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<BigInt, _>(amount)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
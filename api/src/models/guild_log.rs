@@ -1,29 +1,191 @@
-use crate::constants::FETCH_LOGS_MAX;
 use crate::db::schema::guild_log;
 use crate::db::PgPool;
 use crate::routes::ApiResult;
 
 use actix_web::web::block;
-use chrono::NaiveDateTime;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::dsl::*;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
 
-#[derive(Debug, Deserialize, Serialize, Queryable, Identifiable)]
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Identifiable)]
 #[table_name = "guild_log"]
 pub struct GuildLog {
     pub id: i64,
     pub guild: i64,
-    pub action: String,
     pub author: i64,
     pub created_at: NaiveDateTime,
+    pub kind: String,
+    pub payload: Value,
+    pub origin: String,
 }
 
 #[derive(Debug, Deserialize, Insertable)]
 #[table_name = "guild_log"]
 pub struct NewGuildLog {
     pub guild: i64,
-    pub action: String,
     pub author: i64,
+    pub kind: String,
+    pub payload: Value,
+    pub origin: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogOrigin {
+    Dashboard,
+    Command,
+    Api,
+    System,
+}
+
+impl FromStr for LogOrigin {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl LogOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dashboard => "dashboard",
+            Self::Command => "command",
+            Self::Api => "api",
+            Self::System => "system",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogCategory {
+    Playing,
+    Player,
+    Queue,
+    Playlist,
+    Settings,
+}
+
+impl FromStr for LogCategory {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl LogCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Playing => "playing",
+            Self::Player => "player",
+            Self::Queue => "queue",
+            Self::Playlist => "playlist",
+            Self::Settings => "settings",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogKind {
+    Legacy,
+    NowPlaying,
+    TrackStuck,
+    TrackException,
+    WebsocketClose,
+    PlayerAdd,
+    PlayerRemove,
+    PlayerUpdate,
+    QueueAdd,
+    QueueAddFavourites,
+    QueueRemove,
+    QueueShift,
+    QueueClear,
+    QueueShuffle,
+    PlaylistLoad,
+    PlaylistAdd,
+    PlaylistRemove,
+    PlaylistUpdate,
+    PlaylistItemAdd,
+    PlaylistItemRemove,
+    PlaylistItemShift,
+    SettingsUpdate,
+}
+
+impl FromStr for LogKind {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl LogKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Legacy => "legacy",
+            Self::NowPlaying => "now_playing",
+            Self::TrackStuck => "track_stuck",
+            Self::TrackException => "track_exception",
+            Self::WebsocketClose => "websocket_close",
+            Self::PlayerAdd => "player_add",
+            Self::PlayerRemove => "player_remove",
+            Self::PlayerUpdate => "player_update",
+            Self::QueueAdd => "queue_add",
+            Self::QueueAddFavourites => "queue_add_favourites",
+            Self::QueueRemove => "queue_remove",
+            Self::QueueShift => "queue_shift",
+            Self::QueueClear => "queue_clear",
+            Self::QueueShuffle => "queue_shuffle",
+            Self::PlaylistLoad => "playlist_load",
+            Self::PlaylistAdd => "playlist_add",
+            Self::PlaylistRemove => "playlist_remove",
+            Self::PlaylistUpdate => "playlist_update",
+            Self::PlaylistItemAdd => "playlist_item_add",
+            Self::PlaylistItemRemove => "playlist_item_remove",
+            Self::PlaylistItemShift => "playlist_item_shift",
+            Self::SettingsUpdate => "settings_update",
+        }
+    }
+
+    pub fn category(&self) -> Option<LogCategory> {
+        match self {
+            Self::Legacy => None,
+            Self::NowPlaying | Self::TrackStuck | Self::TrackException | Self::WebsocketClose => {
+                Some(LogCategory::Playing)
+            },
+            Self::PlayerAdd | Self::PlayerRemove | Self::PlayerUpdate => Some(LogCategory::Player),
+            Self::QueueAdd
+            | Self::QueueAddFavourites
+            | Self::QueueRemove
+            | Self::QueueShift
+            | Self::QueueClear
+            | Self::QueueShuffle
+            | Self::PlaylistLoad => Some(LogCategory::Queue),
+            Self::PlaylistAdd
+            | Self::PlaylistRemove
+            | Self::PlaylistUpdate
+            | Self::PlaylistItemAdd
+            | Self::PlaylistItemRemove
+            | Self::PlaylistItemShift => Some(LogCategory::Playlist),
+            Self::SettingsUpdate => Some(LogCategory::Settings),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogFilter {
+    pub author: Option<i64>,
+    pub kind: Option<LogKind>,
+    pub origin: Option<LogOrigin>,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub before: Option<GuildLog>,
 }
 
 pub async fn create(pool: &PgPool, new_guild_log: NewGuildLog) -> ApiResult<GuildLog> {
@@ -40,15 +202,65 @@ pub async fn create(pool: &PgPool, new_guild_log: NewGuildLog) -> ApiResult<Guil
     .await?)
 }
 
-pub async fn find_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<GuildLog>> {
+pub async fn find(pool: &PgPool, id: i64) -> ApiResult<Option<GuildLog>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<GuildLog>> {
+        let conn = pool.get()?;
+        let res = guild_log::table.find(id).first(&*conn).optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_guild(
+    pool: &PgPool,
+    guild: i64,
+    filter: LogFilter,
+    limit: i64,
+) -> ApiResult<Vec<GuildLog>> {
     let pool = pool.clone();
 
     Ok(block(move || -> ApiResult<Vec<GuildLog>> {
         let conn = pool.get()?;
-        let res = guild_log::table
+        let mut query = guild_log::table
             .filter(guild_log::guild.eq(guild))
-            .order(guild_log::created_at.desc())
-            .limit(FETCH_LOGS_MAX as i64)
+            .into_boxed();
+
+        if let Some(author) = filter.author {
+            query = query.filter(guild_log::author.eq(author));
+        }
+
+        if let Some(kind) = filter.kind {
+            query = query.filter(guild_log::kind.eq(kind.as_str()));
+        }
+
+        if let Some(origin) = filter.origin {
+            query = query.filter(guild_log::origin.eq(origin.as_str()));
+        }
+
+        if let Some(from) = filter.from {
+            query = query.filter(date(guild_log::created_at).ge(from));
+        }
+
+        if let Some(to) = filter.to {
+            query = query.filter(date(guild_log::created_at).le(to));
+        }
+
+        if let Some(before) = filter.before {
+            query = query.filter(
+                guild_log::created_at
+                    .lt(before.created_at)
+                    .or(guild_log::created_at
+                        .eq(before.created_at)
+                        .and(guild_log::id.lt(before.id))),
+            );
+        }
+
+        let res = query
+            .order((guild_log::created_at.desc(), guild_log::id.desc()))
+            .limit(limit)
             .load(&*conn)?;
 
         Ok(res)
@@ -56,6 +268,20 @@ pub async fn find_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<GuildLog>
     .await?)
 }
 
+pub async fn delete_expired(pool: &PgPool, days: i32) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res =
+            diesel::delete(guild_log::table.filter(guild_log::created_at.lt(now - days.days())))
+                .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
 pub async fn delete_by_guild(pool: &PgPool, id: i64) -> ApiResult<usize> {
     let pool = pool.clone();
 
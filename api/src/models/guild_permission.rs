@@ -0,0 +1,414 @@
+use crate::db::schema::guild_permission;
+use crate::db::PgPool;
+use crate::models::{check_duplicate, Validate};
+use crate::routes::{ApiResponse, ApiResult};
+
+use actix_web::web::block;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable)]
+pub struct GuildPermission {
+    pub guild: i64,
+    pub kind: String,
+    pub target: i64,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "guild_permission"]
+pub struct NewGuildPermission {
+    pub guild: i64,
+    pub kind: String,
+    pub target: i64,
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PermissionKind {
+    Role,
+    User,
+}
+
+impl FromStr for PermissionKind {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl PermissionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Role => "role",
+            Self::User => "user",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    ManageGuild,
+    ManagePlaylist,
+    LoadPlaylist,
+    AddTrack,
+    RemoveTrack,
+    MoveTrack,
+    ClearQueue,
+    Shuffle,
+    Pause,
+    Skip,
+    Seek,
+    Volume,
+    Filters,
+    Loop,
+    Disconnect,
+}
+
+impl FromStr for Permission {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl Permission {
+    pub const ALL: [Permission; 15] = [
+        Self::ManageGuild,
+        Self::ManagePlaylist,
+        Self::LoadPlaylist,
+        Self::AddTrack,
+        Self::RemoveTrack,
+        Self::MoveTrack,
+        Self::ClearQueue,
+        Self::Shuffle,
+        Self::Pause,
+        Self::Skip,
+        Self::Seek,
+        Self::Volume,
+        Self::Filters,
+        Self::Loop,
+        Self::Disconnect,
+    ];
+
+    pub const DEFAULT: [Permission; 13] = [
+        Self::LoadPlaylist,
+        Self::AddTrack,
+        Self::RemoveTrack,
+        Self::MoveTrack,
+        Self::ClearQueue,
+        Self::Shuffle,
+        Self::Pause,
+        Self::Skip,
+        Self::Seek,
+        Self::Volume,
+        Self::Filters,
+        Self::Loop,
+        Self::Disconnect,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ManageGuild => "manage_guild",
+            Self::ManagePlaylist => "manage_playlist",
+            Self::LoadPlaylist => "load_playlist",
+            Self::AddTrack => "add_track",
+            Self::RemoveTrack => "remove_track",
+            Self::MoveTrack => "move_track",
+            Self::ClearQueue => "clear_queue",
+            Self::Shuffle => "shuffle",
+            Self::Pause => "pause",
+            Self::Skip => "skip",
+            Self::Seek => "seek",
+            Self::Volume => "volume",
+            Self::Filters => "filters",
+            Self::Loop => "loop",
+            Self::Disconnect => "disconnect",
+        }
+    }
+}
+
+impl GuildPermission {
+    fn parse(items: &[String]) -> impl Iterator<Item = Permission> + '_ {
+        items
+            .iter()
+            .filter_map(|item| item.parse::<Permission>().ok())
+    }
+
+    fn apply(&self, permissions: &mut HashSet<Permission>) {
+        for permission in Self::parse(&self.deny) {
+            permissions.remove(&permission);
+        }
+
+        permissions.extend(Self::parse(&self.allow));
+    }
+}
+
+impl Validate for NewGuildPermission {
+    fn check(&self) -> ApiResult<()> {
+        if self.kind.parse::<PermissionKind>().is_err() {
+            return Err(ApiResponse::bad_request()
+                .message("error.permission_kind_invalid")
+                .into());
+        }
+
+        if self
+            .allow
+            .iter()
+            .chain(self.deny.iter())
+            .any(|permission| permission.parse::<Permission>().is_err())
+        {
+            return Err(ApiResponse::bad_request()
+                .message("error.permissions_invalid")
+                .into());
+        }
+
+        check_duplicate(self.allow.as_slice(), "field.permission_allow")?;
+        check_duplicate(self.deny.as_slice(), "field.permission_deny")?;
+
+        if self
+            .allow
+            .iter()
+            .any(|permission| self.deny.contains(permission))
+        {
+            return Err(ApiResponse::bad_request()
+                .message("error.permissions_conflict")
+                .into());
+        }
+
+        Ok(())
+    }
+}
+
+pub fn resolve(
+    overrides: &[GuildPermission],
+    guild: u64,
+    user: u64,
+    roles: &[i64],
+) -> HashSet<Permission> {
+    let find = |kind: PermissionKind, target: i64| {
+        overrides
+            .iter()
+            .find(|item| item.kind == kind.as_str() && item.target == target)
+    };
+
+    let mut permissions: HashSet<Permission> = Permission::DEFAULT.iter().copied().collect();
+
+    if let Some(everyone) = find(PermissionKind::Role, guild as i64) {
+        everyone.apply(&mut permissions);
+    }
+
+    let role_overrides: Vec<&GuildPermission> = overrides
+        .iter()
+        .filter(|item| {
+            item.kind == PermissionKind::Role.as_str()
+                && item.target != guild as i64
+                && roles.contains(&item.target)
+        })
+        .collect();
+
+    for role in &role_overrides {
+        for permission in GuildPermission::parse(&role.deny) {
+            permissions.remove(&permission);
+        }
+    }
+
+    for role in &role_overrides {
+        permissions.extend(GuildPermission::parse(&role.allow));
+    }
+
+    if let Some(member) = find(PermissionKind::User, user as i64) {
+        member.apply(&mut permissions);
+    }
+
+    if permissions.contains(&Permission::ManageGuild) {
+        return Permission::ALL.iter().copied().collect();
+    }
+
+    permissions
+}
+
+pub async fn create(
+    pool: &PgPool,
+    new_guild_permission: NewGuildPermission,
+) -> ApiResult<GuildPermission> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<GuildPermission> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(guild_permission::table)
+            .values(&new_guild_permission)
+            .on_conflict((
+                guild_permission::guild,
+                guild_permission::kind,
+                guild_permission::target,
+            ))
+            .do_update()
+            .set((
+                guild_permission::allow.eq(&new_guild_permission.allow),
+                guild_permission::deny.eq(&new_guild_permission.deny),
+            ))
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, guild: i64, kind: String, target: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res =
+            diesel::delete(guild_permission::table.find((guild, kind, target))).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_guild(pool: &PgPool, guild: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(guild_permission::table.filter(guild_permission::guild.eq(guild)))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(
+    pool: &PgPool,
+    guild: i64,
+    kind: String,
+    target: i64,
+) -> ApiResult<Option<GuildPermission>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<GuildPermission>> {
+        let conn = pool.get()?;
+        let res = guild_permission::table
+            .find((guild, kind, target))
+            .first(&*conn)
+            .optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<GuildPermission>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<GuildPermission>> {
+        let conn = pool.get()?;
+        let res = guild_permission::table
+            .filter(guild_permission::guild.eq(guild))
+            .order((guild_permission::kind.asc(), guild_permission::target.asc()))
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: u64 = 1;
+    const USER: u64 = 2;
+
+    fn get_override(
+        kind: PermissionKind,
+        target: i64,
+        allow: &[&str],
+        deny: &[&str],
+    ) -> GuildPermission {
+        GuildPermission {
+            guild: GUILD as i64,
+            kind: kind.as_str().to_owned(),
+            target,
+            allow: allow.iter().map(|item| item.to_string()).collect(),
+            deny: deny.iter().map(|item| item.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn resolve_default() {
+        let permissions = resolve(&[], GUILD, USER, &[]);
+
+        assert_eq!(permissions, Permission::DEFAULT.iter().copied().collect());
+    }
+
+    #[test]
+    fn resolve_everyone_deny() {
+        let overrides = [get_override(
+            PermissionKind::Role,
+            GUILD as i64,
+            &[],
+            &["skip"],
+        )];
+        let permissions = resolve(&overrides, GUILD, USER, &[]);
+
+        assert!(!permissions.contains(&Permission::Skip));
+        assert!(permissions.contains(&Permission::Pause));
+    }
+
+    #[test]
+    fn resolve_role_allow_over_deny() {
+        let overrides = [
+            get_override(PermissionKind::Role, GUILD as i64, &[], &["skip"]),
+            get_override(PermissionKind::Role, 3, &["skip"], &[]),
+            get_override(PermissionKind::Role, 4, &[], &["skip", "seek"]),
+            get_override(PermissionKind::Role, 5, &["manage_guild"], &[]),
+        ];
+        let permissions = resolve(&overrides, GUILD, USER, &[3, 4]);
+
+        assert!(permissions.contains(&Permission::Skip));
+        assert!(!permissions.contains(&Permission::Seek));
+        assert!(!permissions.contains(&Permission::ManageGuild));
+    }
+
+    #[test]
+    fn resolve_user_override() {
+        let overrides = [
+            get_override(PermissionKind::Role, 3, &["manage_playlist"], &[]),
+            get_override(
+                PermissionKind::User,
+                USER as i64,
+                &["volume"],
+                &["manage_playlist"],
+            ),
+            get_override(PermissionKind::User, 6, &["manage_guild"], &[]),
+        ];
+        let permissions = resolve(&overrides, GUILD, USER, &[3]);
+
+        assert!(!permissions.contains(&Permission::ManagePlaylist));
+        assert!(permissions.contains(&Permission::Volume));
+        assert!(!permissions.contains(&Permission::ManageGuild));
+    }
+
+    #[test]
+    fn resolve_manage_guild() {
+        let overrides = [
+            get_override(PermissionKind::Role, GUILD as i64, &[], &["skip"]),
+            get_override(PermissionKind::Role, 3, &["manage_guild"], &[]),
+        ];
+        let permissions = resolve(&overrides, GUILD, USER, &[3]);
+
+        assert_eq!(permissions, Permission::ALL.iter().copied().collect());
+    }
+}
@@ -1,12 +1,11 @@
-use crate::constants::FETCH_STAT_DAYS;
-use crate::db::schema::guild_stat;
+use crate::db::schema::{guild_stat, guild_stat_daily};
 use crate::db::PgPool;
 use crate::routes::ApiResult;
 
 use actix_web::web::block;
-use chrono::NaiveDateTime;
-use diesel::dsl::*;
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Date, Integer, Text};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Queryable, Identifiable)]
@@ -17,6 +16,11 @@ pub struct GuildStat {
     pub author: i64,
     pub title: String,
     pub created_at: NaiveDateTime,
+    pub track: String,
+    pub uri: String,
+    pub length: i32,
+    pub reason: String,
+    pub played: Option<i32>,
 }
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -25,6 +29,115 @@ pub struct NewGuildStat {
     pub guild: i64,
     pub author: i64,
     pub title: String,
+    pub track: String,
+    pub uri: String,
+    pub length: i32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndReason {
+    Finished,
+    Skipped,
+    Stuck,
+    Exception,
+}
+
+impl EndReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Finished => "finished",
+            Self::Skipped => "skipped",
+            Self::Stuck => "stuck",
+            Self::Exception => "exception",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct TrackStat {
+    #[sql_type = "Text"]
+    pub track: String,
+    #[sql_type = "Text"]
+    pub title: String,
+    #[sql_type = "Text"]
+    pub uri: String,
+    #[sql_type = "Integer"]
+    pub length: i32,
+    #[sql_type = "BigInt"]
+    pub plays: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct TrackPlays {
+    #[sql_type = "Text"]
+    pub title: String,
+    #[sql_type = "BigInt"]
+    pub plays: i64,
+    #[sql_type = "BigInt"]
+    pub duration: i64,
+    #[sql_type = "BigInt"]
+    pub finished: i64,
+    #[sql_type = "BigInt"]
+    pub skipped: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct UserPlays {
+    #[sql_type = "BigInt"]
+    pub author: i64,
+    #[sql_type = "BigInt"]
+    pub plays: i64,
+    #[sql_type = "BigInt"]
+    pub duration: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct GuildPlays {
+    #[sql_type = "BigInt"]
+    pub guild: i64,
+    #[sql_type = "BigInt"]
+    pub plays: i64,
+    #[sql_type = "BigInt"]
+    pub duration: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct GlobalDailyPlays {
+    #[sql_type = "Date"]
+    pub day: NaiveDate,
+    #[sql_type = "BigInt"]
+    pub plays: i64,
+    #[sql_type = "BigInt"]
+    pub duration: i64,
+    #[sql_type = "BigInt"]
+    pub guilds: i64,
+    #[sql_type = "BigInt"]
+    pub listeners: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct EndStats {
+    #[sql_type = "BigInt"]
+    pub ended: i64,
+    #[sql_type = "BigInt"]
+    pub finished: i64,
+    #[sql_type = "BigInt"]
+    pub skipped: i64,
+    #[sql_type = "BigInt"]
+    pub stuck: i64,
+    #[sql_type = "BigInt"]
+    pub exception: i64,
+}
+
+#[derive(Debug, Serialize, QueryableByName)]
+pub struct DailyPlays {
+    #[sql_type = "Date"]
+    pub day: NaiveDate,
+    #[sql_type = "BigInt"]
+    pub plays: i64,
+    #[sql_type = "BigInt"]
+    pub duration: i64,
 }
 
 pub async fn create(pool: &PgPool, new_guild_stat: NewGuildStat) -> ApiResult<GuildStat> {
@@ -41,16 +154,268 @@ pub async fn create(pool: &PgPool, new_guild_stat: NewGuildStat) -> ApiResult<Gu
     .await?)
 }
 
-pub async fn find_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<GuildStat>> {
+pub async fn find_track_stats(
+    pool: &PgPool,
+    guild: i64,
+    days: i32,
+    amount: i64,
+) -> ApiResult<Vec<TrackPlays>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<TrackPlays>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT min(title) AS title, sum(plays)::bigint AS plays, sum(duration)::bigint AS \
+             duration, sum(finished)::bigint AS finished, sum(skipped)::bigint AS skipped FROM \
+             guild_stat_source AS stat WHERE guild = $1 AND day >= current_date - ($2 - 1) GROUP \
+             BY key ORDER BY plays DESC, duration DESC LIMIT $3",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<Integer, _>(days)
+        .bind::<BigInt, _>(amount)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_user_stats(
+    pool: &PgPool,
+    guild: i64,
+    days: i32,
+    amount: i64,
+) -> ApiResult<Vec<UserPlays>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<UserPlays>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT author, sum(plays)::bigint AS plays, sum(duration)::bigint AS duration FROM \
+             guild_stat_source AS stat WHERE guild = $1 AND day >= current_date - ($2 - 1) GROUP \
+             BY author ORDER BY plays DESC, duration DESC LIMIT $3",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<Integer, _>(days)
+        .bind::<BigInt, _>(amount)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_end_stats(pool: &PgPool, guild: i64, days: i32) -> ApiResult<EndStats> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<EndStats> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT coalesce(sum(ended), 0)::bigint AS ended, coalesce(sum(finished), 0)::bigint \
+             AS finished, coalesce(sum(skipped), 0)::bigint AS skipped, coalesce(sum(stuck), \
+             0)::bigint AS stuck, coalesce(sum(exception), 0)::bigint AS exception FROM \
+             guild_stat_source AS stat WHERE guild = $1 AND day >= current_date - ($2 - 1)",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<Integer, _>(days)
+        .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_daily_stats(pool: &PgPool, guild: i64, days: i32) -> ApiResult<Vec<DailyPlays>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<DailyPlays>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT series.day::date AS day, coalesce(sum(stat.plays), 0)::bigint AS plays, \
+             coalesce(sum(stat.duration), 0)::bigint AS duration FROM generate_series(current_date \
+             - ($2 - 1), current_date, interval '1 day') AS series(day) LEFT JOIN \
+             guild_stat_source AS stat ON stat.guild = $1 AND stat.day = series.day::date GROUP BY \
+             series.day ORDER BY series.day",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<Integer, _>(days)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_top_tracks(
+    pool: &PgPool,
+    guild: i64,
+    author: i64,
+    days: i32,
+    amount: i64,
+) -> ApiResult<Vec<TrackStat>> {
     let pool = pool.clone();
 
-    Ok(block(move || -> ApiResult<Vec<GuildStat>> {
+    Ok(block(move || -> ApiResult<Vec<TrackStat>> {
         let conn = pool.get()?;
-        let res = guild_stat::table
-            .filter(guild_stat::guild.eq(guild))
-            .filter(guild_stat::created_at.gt(now - (FETCH_STAT_DAYS as i32).days()))
-            .order(guild_stat::created_at.desc())
-            .load(&*conn)?;
+        let res = diesel::sql_query(
+            "SELECT max(track) AS track, min(title) AS title, min(uri) AS uri, min(length) AS \
+             length, sum(plays)::bigint AS plays FROM guild_stat_source AS stat WHERE guild = $1 \
+             AND track <> '' AND ($2 = 0 OR author = $2) AND day >= current_date - ($3 - 1) GROUP \
+             BY key ORDER BY plays DESC, max(day) DESC LIMIT $4",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<BigInt, _>(author)
+        .bind::<Integer, _>(days)
+        .bind::<BigInt, _>(amount)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_global_track_stats(
+    pool: &PgPool,
+    from: NaiveDate,
+    to: NaiveDate,
+    amount: i64,
+) -> ApiResult<Vec<TrackPlays>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<TrackPlays>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT min(title) AS title, sum(plays)::bigint AS plays, sum(duration)::bigint AS \
+             duration, sum(finished)::bigint AS finished, sum(skipped)::bigint AS skipped FROM \
+             guild_stat_source AS stat WHERE day >= $1 AND day <= $2 GROUP BY key ORDER BY plays \
+             DESC, duration DESC LIMIT $3",
+        )
+        .bind::<Date, _>(from)
+        .bind::<Date, _>(to)
+        .bind::<BigInt, _>(amount)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_global_guild_stats(
+    pool: &PgPool,
+    from: NaiveDate,
+    to: NaiveDate,
+    amount: i64,
+) -> ApiResult<Vec<GuildPlays>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<GuildPlays>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT guild, sum(plays)::bigint AS plays, sum(duration)::bigint AS duration FROM \
+             guild_stat_source AS stat WHERE day >= $1 AND day <= $2 GROUP BY guild ORDER BY plays \
+             DESC, duration DESC LIMIT $3",
+        )
+        .bind::<Date, _>(from)
+        .bind::<Date, _>(to)
+        .bind::<BigInt, _>(amount)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_global_daily_stats(
+    pool: &PgPool,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> ApiResult<Vec<GlobalDailyPlays>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<GlobalDailyPlays>> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "SELECT series.day::date AS day, coalesce(stat.plays, 0)::bigint AS plays, \
+             coalesce(stat.duration, 0)::bigint AS duration, coalesce(stat.guilds, 0) AS guilds, \
+             coalesce(listener.listeners, 0) AS listeners FROM generate_series($1, $2, interval '1 \
+             day') AS series(day) LEFT JOIN (SELECT day, sum(plays) AS plays, sum(duration) AS \
+             duration, count(DISTINCT guild) AS guilds FROM guild_stat_source AS stat WHERE day >= \
+             $1 AND day <= $2 GROUP BY day) AS stat ON stat.day = series.day::date LEFT JOIN \
+             (SELECT created_at::date AS day, count(DISTINCT account) AS listeners FROM history \
+             WHERE created_at >= $1 AND created_at < $2 + 1 GROUP BY created_at::date) AS listener \
+             ON listener.day = series.day::date ORDER BY series.day",
+        )
+        .bind::<Date, _>(from)
+        .bind::<Date, _>(to)
+        .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn set_reason(
+    pool: &PgPool,
+    guild: i64,
+    track: String,
+    reason: EndReason,
+) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "UPDATE guild_stat SET reason = $3, played = CASE WHEN $3 = 'finished' THEN length \
+             ELSE least(length, (extract(epoch FROM localtimestamp - created_at) * 1000)::int) END \
+             WHERE id = (SELECT id FROM guild_stat WHERE guild = $1 ORDER BY created_at DESC, id \
+             DESC LIMIT 1) AND track = $2 AND reason = ''",
+        )
+        .bind::<BigInt, _>(guild)
+        .bind::<Text, _>(track)
+        .bind::<Text, _>(reason.as_str())
+        .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn roll_up(pool: &PgPool) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "INSERT INTO guild_stat_daily (guild, day, key, track, title, uri, length, author, \
+             plays, duration, finished, skipped, stuck, exception, ended) SELECT guild, day, key, \
+             max(track), min(title), min(uri), min(length), author, sum(plays), sum(duration), \
+             sum(finished), sum(skipped), sum(stuck), sum(exception), sum(ended) FROM \
+             guild_stat_raw AS stat WHERE day >= coalesce((SELECT max(day) - 1 FROM \
+             guild_stat_daily), '-infinity'::date) GROUP BY guild, day, key, author ON CONFLICT \
+             (guild, day, key, author) DO UPDATE SET track = excluded.track, title = \
+             excluded.title, uri = excluded.uri, length = excluded.length, plays = excluded.plays, \
+             duration = excluded.duration, finished = excluded.finished, skipped = \
+             excluded.skipped, stuck = excluded.stuck, exception = excluded.exception, ended = \
+             excluded.ended",
+        )
+        .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_expired(pool: &PgPool, days: i32) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::sql_query(
+            "DELETE FROM guild_stat WHERE created_at < current_date - $1 AND created_at < (SELECT \
+             max(day) - 1 FROM guild_stat_daily)",
+        )
+        .bind::<Integer, _>(days)
+        .execute(&*conn)?;
 
         Ok(res)
     })
@@ -62,8 +427,12 @@ pub async fn delete_by_guild(pool: &PgPool, id: i64) -> ApiResult<usize> {
 
     Ok(block(move || -> ApiResult<usize> {
         let conn = pool.get()?;
-        let res =
-            diesel::delete(guild_stat::table.filter(guild_stat::guild.eq(id))).execute(&*conn)?;
+        let res = conn.transaction::<_, diesel::result::Error, _>(|| {
+            diesel::delete(guild_stat_daily::table.filter(guild_stat_daily::guild.eq(id)))
+                .execute(&*conn)?;
+
+            diesel::delete(guild_stat::table.filter(guild_stat::guild.eq(id))).execute(&*conn)
+        })?;
 
         Ok(res)
     })
@@ -0,0 +1,231 @@
+use crate::constants::{
+    WEBHOOK_ERROR_MAX, WEBHOOK_TOKEN_MASK, WEBHOOK_URL_HOSTS, WEBHOOK_URL_PATH,
+};
+use crate::db::schema::guild_webhook;
+use crate::db::PgPool;
+use crate::models::guild_log::LogCategory;
+use crate::models::Validate;
+use crate::routes::{ApiResponse, ApiResult};
+
+use actix_web::web::block;
+use chrono::NaiveDateTime;
+use diesel::dsl::now;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+use url::Url;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable)]
+pub struct GuildWebhook {
+    pub guild: i64,
+    pub category: String,
+    #[serde(serialize_with = "mask_url")]
+    pub url: String,
+    pub failures: i32,
+    pub last_error: String,
+    pub last_error_at: Option<NaiveDateTime>,
+    pub last_delivered_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "guild_webhook"]
+pub struct NewGuildWebhook {
+    pub guild: i64,
+    pub category: String,
+    pub url: String,
+}
+
+fn mask_url<S: Serializer>(url: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let masked = match url.trim_end_matches('/').rfind('/') {
+        Some(index) => format!("{}/{}", &url[..index], "*".repeat(WEBHOOK_TOKEN_MASK)),
+        None => "*".repeat(WEBHOOK_TOKEN_MASK),
+    };
+
+    serializer.serialize_str(masked.as_str())
+}
+
+impl Validate for NewGuildWebhook {
+    fn check(&self) -> ApiResult<()> {
+        if self.category.parse::<LogCategory>().is_err() {
+            return Err(ApiResponse::bad_request()
+                .message("error.log_category_invalid")
+                .into());
+        }
+
+        let is_valid = Url::parse(self.url.as_str())
+            .map(|url| {
+                url.scheme() == "https"
+                    && url.username().is_empty()
+                    && url.password().is_none()
+                    && url.port().is_none()
+                    && url
+                        .host_str()
+                        .map(|host| WEBHOOK_URL_HOSTS.contains(&host))
+                        .unwrap_or(false)
+                    && url.path().starts_with(WEBHOOK_URL_PATH)
+            })
+            .unwrap_or(false);
+
+        if !is_valid {
+            return Err(ApiResponse::bad_request()
+                .message("error.webhook_invalid")
+                .into());
+        }
+
+        Ok(())
+    }
+}
+
+pub async fn create(pool: &PgPool, new_guild_webhook: NewGuildWebhook) -> ApiResult<GuildWebhook> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<GuildWebhook> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(guild_webhook::table)
+            .values(&new_guild_webhook)
+            .on_conflict((guild_webhook::guild, guild_webhook::category))
+            .do_update()
+            .set((
+                guild_webhook::url.eq(&new_guild_webhook.url),
+                guild_webhook::failures.eq(0),
+                guild_webhook::last_error.eq(""),
+                guild_webhook::last_error_at.eq(None::<NaiveDateTime>),
+                guild_webhook::last_delivered_at.eq(None::<NaiveDateTime>),
+            ))
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn set_delivered(pool: &PgPool, guild: i64, category: String) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::update(guild_webhook::table.find((guild, category)))
+            .set((
+                guild_webhook::failures.eq(0),
+                guild_webhook::last_delivered_at.eq(now),
+            ))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn set_failed(
+    pool: &PgPool,
+    guild: i64,
+    category: String,
+    error: String,
+) -> ApiResult<usize> {
+    let pool = pool.clone();
+    let error = error.chars().take(WEBHOOK_ERROR_MAX).collect::<String>();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::update(guild_webhook::table.find((guild, category)))
+            .set((
+                guild_webhook::failures.eq(guild_webhook::failures + 1),
+                guild_webhook::last_error.eq(error),
+                guild_webhook::last_error_at.eq(now),
+            ))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, guild: i64, category: String) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(guild_webhook::table.find((guild, category))).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_guild(pool: &PgPool, guild: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(guild_webhook::table.filter(guild_webhook::guild.eq(guild)))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(pool: &PgPool, guild: i64, category: String) -> ApiResult<Option<GuildWebhook>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<GuildWebhook>> {
+        let conn = pool.get()?;
+        let res = guild_webhook::table
+            .find((guild, category))
+            .first(&*conn)
+            .optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<GuildWebhook>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<GuildWebhook>> {
+        let conn = pool.get()?;
+        let res = guild_webhook::table
+            .filter(guild_webhook::guild.eq(guild))
+            .order(guild_webhook::category.asc())
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_url(url: &str) -> bool {
+        NewGuildWebhook {
+            guild: 1,
+            category: "queue".to_owned(),
+            url: url.to_owned(),
+        }
+        .check()
+        .is_ok()
+    }
+
+    #[test]
+    fn check_valid_url() {
+        assert!(check_url("https://discord.com/api/webhooks/1/token"));
+        assert!(check_url("https://ptb.discord.com/api/webhooks/1/token"));
+    }
+
+    #[test]
+    fn check_invalid_url() {
+        assert!(!check_url("http://discord.com/api/webhooks/1/token"));
+        assert!(!check_url(
+            "https://discord.com.example.com/api/webhooks/1/token"
+        ));
+        assert!(!check_url(
+            "https://discord.com@example.com/api/webhooks/1/token"
+        ));
+        assert!(!check_url("https://discord.com:8443/api/webhooks/1/token"));
+        assert!(!check_url("https://discord.com/api/users/1"));
+        assert!(!check_url("discord.com/api/webhooks/1/token"));
+    }
+}
@@ -0,0 +1,114 @@
+use crate::db::schema::history;
+use crate::db::PgPool;
+use crate::routes::ApiResult;
+
+use actix_web::web::block;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Queryable, Identifiable)]
+#[table_name = "history"]
+pub struct History {
+    pub id: i64,
+    pub account: i64,
+    pub guild: i64,
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "history"]
+pub struct NewHistory {
+    pub account: i64,
+    pub guild: i64,
+    pub track: String,
+    pub title: String,
+    pub uri: String,
+    pub length: i32,
+}
+
+pub async fn batch_create(pool: &PgPool, new_histories: Vec<NewHistory>) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(history::table)
+            .values(new_histories)
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_account(pool: &PgPool, account: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res =
+            diesel::delete(history::table.filter(history::account.eq(account))).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_account(pool: &PgPool, account: i64) -> ApiResult<Vec<History>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<History>> {
+        let conn = pool.get()?;
+        let res = history::table
+            .filter(history::account.eq(account))
+            .order(history::created_at.desc())
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_page_by_account(
+    pool: &PgPool,
+    account: i64,
+    guilds: Vec<i64>,
+    offset: i64,
+    limit: i64,
+) -> ApiResult<Vec<History>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<History>> {
+        let conn = pool.get()?;
+        let res = history::table
+            .filter(history::account.eq(account))
+            .filter(history::guild.eq_any(guilds))
+            .order((history::created_at.desc(), history::id.desc()))
+            .offset(offset)
+            .limit(limit)
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_guilds_by_account(pool: &PgPool, account: i64) -> ApiResult<Vec<i64>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<i64>> {
+        let conn = pool.get()?;
+        let res = history::table
+            .filter(history::account.eq(account))
+            .select(history::guild)
+            .distinct()
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
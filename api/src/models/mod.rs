@@ -1,4 +1,5 @@
 use crate::routes::{ApiResponse, ApiResult};
+use crate::utils::locale::Translation;
 
 use serde::{de, Deserialize, Deserializer};
 use std::cmp::PartialOrd;
@@ -10,11 +11,18 @@ use std::str::FromStr;
 pub mod account;
 pub mod blacklist;
 pub mod config;
+pub mod favourite;
 pub mod guild;
 pub mod guild_log;
+pub mod guild_permission;
 pub mod guild_stat;
+pub mod guild_webhook;
+pub mod history;
 pub mod playlist;
 pub mod playlist_item;
+pub mod user_config;
+pub mod user_session;
+pub mod user_token;
 
 pub trait Validate {
     fn check(&self) -> ApiResult<()>;
@@ -30,7 +38,11 @@ impl<T: Display, U: PartialOrd<T>> ValidateExt<T> for U {
     fn check_min(&self, num: T, name: &str) -> ApiResult<()> {
         if self.lt(&num) {
             Err(ApiResponse::bad_request()
-                .message(format!("The {} should be above {}.", &name, &num).as_str())
+                .message(
+                    Translation::new("validate.above")
+                        .arg_key("name", name)
+                        .arg("num", num),
+                )
                 .into())
         } else {
             Ok(())
@@ -40,7 +52,11 @@ impl<T: Display, U: PartialOrd<T>> ValidateExt<T> for U {
     fn check_max(&self, num: T, name: &str) -> ApiResult<()> {
         if self.gt(&num) {
             Err(ApiResponse::bad_request()
-                .message(format!("The {} should be below {}.", &name, &num).as_str())
+                .message(
+                    Translation::new("validate.below")
+                        .arg_key("name", name)
+                        .arg("num", num),
+                )
                 .into())
         } else {
             Ok(())
@@ -50,7 +66,12 @@ impl<T: Display, U: PartialOrd<T>> ValidateExt<T> for U {
     fn check_btw(&self, min: T, max: T, name: &str) -> ApiResult<()> {
         if self.lt(&min) || self.gt(&max) {
             Err(ApiResponse::bad_request()
-                .message(format!("The {} should be between {} and {}.", &name, &min, &max).as_str())
+                .message(
+                    Translation::new("validate.between")
+                        .arg_key("name", name)
+                        .arg("min", min)
+                        .arg("max", max),
+                )
                 .into())
         } else {
             Ok(())
@@ -63,7 +84,7 @@ pub fn check_duplicate<T: Hash + Eq>(items: &[T], name: &str) -> ApiResult<()> {
 
     if items_set.len() != items.len() {
         Err(ApiResponse::bad_request()
-            .message(format!("The {} cannot contain duplicates.", &name).as_str())
+            .message(Translation::new("validate.duplicate").arg_key("name", name))
             .into())
     } else {
         Ok(())
@@ -1,31 +1,52 @@
-use crate::constants::{PLAYLIST_NAME_MAX, PLAYLIST_NAME_MIN};
-use crate::db::schema::playlist;
+use crate::constants::{
+    PLAYLIST_NAME_MAX, PLAYLIST_NAME_MIN, SMART_PLAYLIST_AMOUNT_MAX, SMART_PLAYLIST_DAYS_MAX,
+};
+use crate::db::schema::{playlist, playlist_item};
 use crate::db::PgPool;
+use crate::models::playlist_item::NewPlaylistItem;
 use crate::models::{Validate, ValidateExt};
 use crate::routes::ApiResult;
 
 use actix_web::web::block;
 use chrono::NaiveDateTime;
+use diesel::dsl::sql;
 use diesel::prelude::*;
 use diesel::result::Error::QueryBuilderError;
+use diesel::sql_types::{BigInt, Nullable};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Queryable, Identifiable)]
 #[table_name = "playlist"]
 pub struct Playlist {
     pub id: i64,
-    pub guild: i64,
+    pub guild: Option<i64>,
     pub name: String,
     pub author: i64,
     pub created_at: NaiveDateTime,
+    pub smart: bool,
+    pub smart_author: i64,
+    pub smart_days: i32,
+    pub smart_amount: i32,
 }
 
 #[derive(Debug, Clone, Deserialize, Insertable)]
 #[table_name = "playlist"]
 pub struct NewPlaylist {
+    pub guild: Option<i64>,
+    pub name: String,
+    pub author: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Insertable)]
+#[table_name = "playlist"]
+pub struct NewSmartPlaylist {
     pub guild: i64,
     pub name: String,
     pub author: i64,
+    pub smart: bool,
+    pub smart_author: i64,
+    pub smart_days: i32,
+    pub smart_amount: i32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, AsChangeset)]
@@ -34,11 +55,64 @@ pub struct EditPlaylist {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct PlaylistSummary {
+    pub id: i64,
+    pub guild: Option<i64>,
+    pub name: String,
+    pub author: i64,
+    pub created_at: NaiveDateTime,
+    pub smart: bool,
+    pub smart_author: i64,
+    pub smart_days: i32,
+    pub smart_amount: i32,
+    pub items: i64,
+    pub length: i64,
+}
+
+impl From<(Playlist, i64, Option<i64>)> for PlaylistSummary {
+    fn from((playlist, items, length): (Playlist, i64, Option<i64>)) -> Self {
+        Self {
+            id: playlist.id,
+            guild: playlist.guild,
+            name: playlist.name,
+            author: playlist.author,
+            created_at: playlist.created_at,
+            smart: playlist.smart,
+            smart_author: playlist.smart_author,
+            smart_days: playlist.smart_days,
+            smart_amount: playlist.smart_amount,
+            items,
+            length: length.unwrap_or_default(),
+        }
+    }
+}
+
+impl Playlist {
+    pub fn is_personal(&self) -> bool {
+        self.guild.is_none()
+    }
+}
+
 impl Validate for NewPlaylist {
     fn check(&self) -> ApiResult<()> {
         self.name
             .len()
-            .check_btw(PLAYLIST_NAME_MIN, PLAYLIST_NAME_MAX, "length of name")?;
+            .check_btw(PLAYLIST_NAME_MIN, PLAYLIST_NAME_MAX, "field.name_length")?;
+
+        Ok(())
+    }
+}
+
+impl Validate for NewSmartPlaylist {
+    fn check(&self) -> ApiResult<()> {
+        self.name
+            .len()
+            .check_btw(PLAYLIST_NAME_MIN, PLAYLIST_NAME_MAX, "field.name_length")?;
+        self.smart_days
+            .check_btw(1, SMART_PLAYLIST_DAYS_MAX as i32, "field.days")?;
+        self.smart_amount
+            .check_btw(1, SMART_PLAYLIST_AMOUNT_MAX as i32, "field.tracks")?;
 
         Ok(())
     }
@@ -48,14 +122,50 @@ impl Validate for EditPlaylist {
     fn check(&self) -> ApiResult<()> {
         if let Some(name) = &self.name {
             name.len()
-                .check_btw(PLAYLIST_NAME_MIN, PLAYLIST_NAME_MAX, "length of name")?;
+                .check_btw(PLAYLIST_NAME_MIN, PLAYLIST_NAME_MAX, "field.name_length")?;
         }
 
         Ok(())
     }
 }
 
-pub async fn create(pool: &PgPool, new_playlist: NewPlaylist) -> ApiResult<Playlist> {
+pub async fn create(
+    pool: &PgPool,
+    new_playlist: NewPlaylist,
+    new_playlist_items: Vec<NewPlaylistItem>,
+) -> ApiResult<Playlist> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Playlist> {
+        let conn = pool.get()?;
+        let res = conn.transaction::<_, diesel::result::Error, _>(|| {
+            let playlist: Playlist = diesel::insert_into(playlist::table)
+                .values(new_playlist)
+                .get_result(&*conn)?;
+
+            let items: Vec<NewPlaylistItem> = new_playlist_items
+                .into_iter()
+                .map(|item| NewPlaylistItem {
+                    playlist: playlist.id,
+                    ..item
+                })
+                .collect();
+
+            if !items.is_empty() {
+                diesel::insert_into(playlist_item::table)
+                    .values(items)
+                    .execute(&*conn)?;
+            }
+
+            Ok(playlist)
+        })?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn create_smart(pool: &PgPool, new_playlist: NewSmartPlaylist) -> ApiResult<Playlist> {
     let pool = pool.clone();
 
     Ok(block(move || -> ApiResult<Playlist> {
@@ -107,7 +217,25 @@ pub async fn delete_by_guild(pool: &PgPool, id: i64) -> ApiResult<usize> {
 
     Ok(block(move || -> ApiResult<usize> {
         let conn = pool.get()?;
-        let res = diesel::delete(playlist::table.filter(playlist::guild.eq(id))).execute(&*conn)?;
+        let res =
+            diesel::delete(playlist::table.filter(playlist::guild.eq(Some(id)))).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_author(pool: &PgPool, author: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(
+            playlist::table
+                .filter(playlist::guild.is_null())
+                .filter(playlist::author.eq(author)),
+        )
+        .execute(&*conn)?;
 
         Ok(res)
     })
@@ -132,10 +260,70 @@ pub async fn find_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<Playlist>
     Ok(block(move || -> ApiResult<Vec<Playlist>> {
         let conn = pool.get()?;
         let res = playlist::table
-            .filter(playlist::guild.eq(guild))
+            .filter(playlist::guild.eq(Some(guild)))
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_author(pool: &PgPool, author: i64) -> ApiResult<Vec<Playlist>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<Playlist>> {
+        let conn = pool.get()?;
+        let res = playlist::table
+            .filter(playlist::guild.is_null())
+            .filter(playlist::author.eq(author))
             .load(&*conn)?;
 
         Ok(res)
     })
     .await?)
 }
+
+pub async fn find_summary_by_guild(pool: &PgPool, guild: i64) -> ApiResult<Vec<PlaylistSummary>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<PlaylistSummary>> {
+        let conn = pool.get()?;
+        let res: Vec<(Playlist, i64, Option<i64>)> = playlist::table
+            .left_join(playlist_item::table)
+            .filter(playlist::guild.eq(Some(guild)))
+            .group_by(playlist::id)
+            .select((
+                playlist::all_columns,
+                sql::<BigInt>("count(playlist_item.id)"),
+                sql::<Nullable<BigInt>>("sum(playlist_item.length)"),
+            ))
+            .order(playlist::id.asc())
+            .load(&*conn)?;
+
+        Ok(res.into_iter().map(PlaylistSummary::from).collect())
+    })
+    .await?)
+}
+
+pub async fn find_summary_by_author(pool: &PgPool, author: i64) -> ApiResult<Vec<PlaylistSummary>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<PlaylistSummary>> {
+        let conn = pool.get()?;
+        let res: Vec<(Playlist, i64, Option<i64>)> = playlist::table
+            .left_join(playlist_item::table)
+            .filter(playlist::guild.is_null())
+            .filter(playlist::author.eq(author))
+            .group_by(playlist::id)
+            .select((
+                playlist::all_columns,
+                sql::<BigInt>("count(playlist_item.id)"),
+                sql::<Nullable<BigInt>>("sum(playlist_item.length)"),
+            ))
+            .order(playlist::id.asc())
+            .load(&*conn)?;
+
+        Ok(res.into_iter().map(PlaylistSummary::from).collect())
+    })
+    .await?)
+}
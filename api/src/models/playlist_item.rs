@@ -1,6 +1,6 @@
-use crate::db::schema::playlist_item;
+use crate::db::schema::{playlist, playlist_item};
 use crate::db::PgPool;
-use crate::routes::ApiResult;
+use crate::routes::{ApiError, ApiResponse, ApiResult};
 
 use actix_web::web::block;
 use diesel::prelude::*;
@@ -15,6 +15,7 @@ pub struct PlaylistItem {
     pub title: String,
     pub uri: String,
     pub length: i32,
+    pub position: i32,
 }
 
 #[derive(Debug, Deserialize, Insertable)]
@@ -25,30 +26,175 @@ pub struct NewPlaylistItem {
     pub title: String,
     pub uri: String,
     pub length: i32,
+    pub position: i32,
 }
 
-pub async fn create(pool: &PgPool, new_playlist_item: NewPlaylistItem) -> ApiResult<PlaylistItem> {
+fn lock_playlist(conn: &PgConnection, id: i64) -> QueryResult<i32> {
+    playlist::table
+        .find(id)
+        .select(playlist::id)
+        .for_update()
+        .first::<i64>(conn)?;
+
+    let count: i64 = playlist_item::table
+        .filter(playlist_item::playlist.eq(id))
+        .count()
+        .get_result(conn)?;
+
+    Ok(count as i32)
+}
+
+fn check_full(amount: i32, added: usize, max: usize) -> ApiResult<()> {
+    if amount as usize + added > max {
+        return Err(ApiResponse::bad_request()
+            .message("error.playlist_full")
+            .into());
+    }
+
+    Ok(())
+}
+
+pub async fn create(
+    pool: &PgPool,
+    new_playlist_item: NewPlaylistItem,
+    max: usize,
+) -> ApiResult<PlaylistItem> {
     let pool = pool.clone();
 
     Ok(block(move || -> ApiResult<PlaylistItem> {
         let conn = pool.get()?;
-        let res = diesel::insert_into(playlist_item::table)
-            .values(new_playlist_item)
-            .get_result(&*conn)?;
+        let res = conn.transaction::<_, ApiError, _>(|| {
+            let position = lock_playlist(&conn, new_playlist_item.playlist)?;
+            check_full(position, 1, max)?;
+
+            diesel::insert_into(playlist_item::table)
+                .values(NewPlaylistItem {
+                    position: position + new_playlist_item.position,
+                    ..new_playlist_item
+                })
+                .get_result(&*conn)
+                .map_err(ApiError::from)
+        })?;
 
         Ok(res)
     })
     .await?)
 }
 
-pub async fn delete_by_playlist(pool: &PgPool, playlist: i64) -> ApiResult<usize> {
+pub async fn batch_create(
+    pool: &PgPool,
+    playlist: i64,
+    new_playlist_items: Vec<NewPlaylistItem>,
+    max: usize,
+) -> ApiResult<usize> {
     let pool = pool.clone();
 
     Ok(block(move || -> ApiResult<usize> {
         let conn = pool.get()?;
-        let res = diesel::delete(playlist_item::table.filter(playlist_item::playlist.eq(playlist)))
+        let res = conn.transaction::<_, ApiError, _>(|| {
+            let position = lock_playlist(&conn, playlist)?;
+            check_full(position, new_playlist_items.len(), max)?;
+            let items: Vec<NewPlaylistItem> = new_playlist_items
+                .into_iter()
+                .map(|item| NewPlaylistItem {
+                    playlist,
+                    position: position + item.position,
+                    ..item
+                })
+                .collect();
+
+            diesel::insert_into(playlist_item::table)
+                .values(items)
+                .execute(&*conn)
+                .map_err(ApiError::from)
+        })?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn shift(pool: &PgPool, id: i64, position: i32) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = conn.transaction::<_, diesel::result::Error, _>(|| {
+            let playlist = playlist_item::table
+                .find(id)
+                .select(playlist_item::playlist)
+                .first(&*conn)?;
+            lock_playlist(&conn, playlist)?;
+
+            let item: PlaylistItem = playlist_item::table.find(id).first(&*conn)?;
+            let items = playlist_item::table.filter(playlist_item::playlist.eq(item.playlist));
+
+            if position > item.position {
+                diesel::update(
+                    items
+                        .filter(playlist_item::position.gt(item.position))
+                        .filter(playlist_item::position.le(position)),
+                )
+                .set(playlist_item::position.eq(playlist_item::position - 1))
+                .execute(&*conn)?;
+            } else if position < item.position {
+                diesel::update(
+                    items
+                        .filter(playlist_item::position.ge(position))
+                        .filter(playlist_item::position.lt(item.position)),
+                )
+                .set(playlist_item::position.eq(playlist_item::position + 1))
+                .execute(&*conn)?;
+            }
+
+            diesel::update(playlist_item::table.find(id))
+                .set(playlist_item::position.eq(position))
+                .execute(&*conn)
+        })?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, id: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = conn.transaction::<_, diesel::result::Error, _>(|| {
+            let playlist = playlist_item::table
+                .find(id)
+                .select(playlist_item::playlist)
+                .first(&*conn)?;
+            lock_playlist(&conn, playlist)?;
+
+            let item: PlaylistItem = playlist_item::table.find(id).first(&*conn)?;
+            diesel::delete(playlist_item::table.find(id)).execute(&*conn)?;
+
+            diesel::update(
+                playlist_item::table
+                    .filter(playlist_item::playlist.eq(item.playlist))
+                    .filter(playlist_item::position.gt(item.position)),
+            )
+            .set(playlist_item::position.eq(playlist_item::position - 1))
             .execute(&*conn)?;
 
+            Ok(1)
+        })?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(pool: &PgPool, id: i64) -> ApiResult<Option<PlaylistItem>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<PlaylistItem>> {
+        let conn = pool.get()?;
+        let res = playlist_item::table.find(id).first(&*conn).optional()?;
+
         Ok(res)
     })
     .await?)
@@ -61,9 +207,47 @@ pub async fn find_by_playlist(pool: &PgPool, playlist: i64) -> ApiResult<Vec<Pla
         let conn = pool.get()?;
         let res = playlist_item::table
             .filter(playlist_item::playlist.eq(playlist))
+            .order(playlist_item::position.asc())
             .load(&*conn)?;
 
         Ok(res)
     })
     .await?)
 }
+
+pub async fn find_page_by_playlist(
+    pool: &PgPool,
+    playlist: i64,
+    offset: i64,
+    limit: i64,
+) -> ApiResult<Vec<PlaylistItem>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<PlaylistItem>> {
+        let conn = pool.get()?;
+        let res = playlist_item::table
+            .filter(playlist_item::playlist.eq(playlist))
+            .order(playlist_item::position.asc())
+            .offset(offset)
+            .limit(limit)
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn count_by_playlist(pool: &PgPool, playlist: i64) -> ApiResult<i64> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<i64> {
+        let conn = pool.get()?;
+        let res = playlist_item::table
+            .filter(playlist_item::playlist.eq(playlist))
+            .count()
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
@@ -0,0 +1,84 @@
+use crate::db::schema::user_config;
+use crate::db::PgPool;
+use crate::models::Validate;
+use crate::routes::{ApiResponse, ApiResult};
+use crate::utils::locale;
+
+use actix_web::web::block;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize, Queryable, Identifiable, Insertable)]
+#[table_name = "user_config"]
+pub struct UserConfig {
+    pub id: i64,
+    pub language: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, AsChangeset)]
+#[table_name = "user_config"]
+pub struct EditUserConfig {
+    pub language: Option<String>,
+}
+
+impl Validate for EditUserConfig {
+    fn check(&self) -> ApiResult<()> {
+        if let Some(language) = &self.language {
+            if !language.is_empty() && !locale::is_supported(language.as_str()) {
+                return Err(ApiResponse::bad_request()
+                    .message("error.language_invalid")
+                    .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub async fn update(
+    pool: &PgPool,
+    id: i64,
+    edit_user_config: EditUserConfig,
+) -> ApiResult<UserConfig> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<UserConfig> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(user_config::table)
+            .values(UserConfig {
+                id,
+                language: edit_user_config.language.clone().unwrap_or_default(),
+            })
+            .on_conflict(user_config::id)
+            .do_update()
+            .set(&edit_user_config)
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, id: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(user_config::table.find(id)).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(pool: &PgPool, id: i64) -> ApiResult<Option<UserConfig>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<UserConfig>> {
+        let conn = pool.get()?;
+        let res = user_config::table.find(id).first(&*conn).optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
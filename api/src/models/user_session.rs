@@ -0,0 +1,205 @@
+use crate::constants::{SESSION_IDLE_MAX, SESSION_REFRESH_MARGIN, SESSION_SEEN_INTERVAL};
+use crate::db::schema::user_session;
+use crate::db::PgPool;
+use crate::routes::ApiResult;
+
+use actix_web::web::block;
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::dsl::now;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Identifiable)]
+#[table_name = "user_session"]
+pub struct UserSession {
+    pub id: i64,
+    pub author: i64,
+    pub token: String,
+    pub refresh_token: String,
+    pub token_expires_at: NaiveDateTime,
+    pub device: String,
+    pub expires_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "user_session"]
+pub struct NewUserSession {
+    pub author: i64,
+    pub token: String,
+    pub refresh_token: String,
+    pub token_expires_at: NaiveDateTime,
+    pub device: String,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, AsChangeset)]
+#[table_name = "user_session"]
+pub struct EditUserSession {
+    pub token: String,
+    pub refresh_token: String,
+    pub token_expires_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserSessionSummary {
+    pub id: i64,
+    pub device: String,
+    pub expires_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<UserSession> for UserSessionSummary {
+    fn from(user_session: UserSession) -> Self {
+        Self {
+            id: user_session.id,
+            device: user_session.device,
+            expires_at: user_session.expires_at,
+            last_seen_at: user_session.last_seen_at,
+            created_at: user_session.created_at,
+        }
+    }
+}
+
+impl UserSession {
+    pub fn is_expired(&self) -> bool {
+        let current = Utc::now().naive_utc();
+
+        self.expires_at <= current
+            || self.last_seen_at + Duration::seconds(SESSION_IDLE_MAX) <= current
+    }
+
+    pub fn is_token_expired(&self) -> bool {
+        self.token_expires_at <= Utc::now().naive_utc()
+    }
+
+    pub fn needs_refresh(&self) -> bool {
+        self.token_expires_at <= Utc::now().naive_utc() + Duration::seconds(SESSION_REFRESH_MARGIN)
+    }
+
+    pub fn is_seen_stale(&self) -> bool {
+        self.last_seen_at + Duration::seconds(SESSION_SEEN_INTERVAL) <= Utc::now().naive_utc()
+    }
+}
+
+pub async fn create(pool: &PgPool, new_user_session: NewUserSession) -> ApiResult<UserSession> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<UserSession> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(user_session::table)
+            .values(new_user_session)
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn update(
+    pool: &PgPool,
+    id: i64,
+    edit_user_session: EditUserSession,
+) -> ApiResult<UserSession> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<UserSession> {
+        let conn = pool.get()?;
+        let res = diesel::update(user_session::table.find(id))
+            .set(&edit_user_session)
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn update_last_seen(pool: &PgPool, id: i64) -> ApiResult<UserSession> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<UserSession> {
+        let conn = pool.get()?;
+        let res = diesel::update(user_session::table.find(id))
+            .set(user_session::last_seen_at.eq(now))
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, id: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(user_session::table.find(id)).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_author(pool: &PgPool, author: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(user_session::table.filter(user_session::author.eq(author)))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_expired_by_author(pool: &PgPool, author: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let idle = Utc::now().naive_utc() - Duration::seconds(SESSION_IDLE_MAX);
+        let res = diesel::delete(
+            user_session::table
+                .filter(user_session::author.eq(author))
+                .filter(
+                    user_session::expires_at
+                        .le(now)
+                        .or(user_session::last_seen_at.le(idle)),
+                ),
+        )
+        .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(pool: &PgPool, id: i64) -> ApiResult<Option<UserSession>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<UserSession>> {
+        let conn = pool.get()?;
+        let res = user_session::table.find(id).first(&*conn).optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_author(pool: &PgPool, author: i64) -> ApiResult<Vec<UserSession>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<UserSession>> {
+        let conn = pool.get()?;
+        let res = user_session::table
+            .filter(user_session::author.eq(author))
+            .order(user_session::last_seen_at.desc())
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
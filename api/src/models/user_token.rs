@@ -0,0 +1,215 @@
+use crate::constants::{USER_TOKEN_NAME_MAX, USER_TOKEN_NAME_MIN};
+use crate::db::schema::user_token;
+use crate::db::PgPool;
+use crate::models::{check_duplicate, Validate, ValidateExt};
+use crate::routes::{ApiResponse, ApiResult};
+
+use actix_web::web::block;
+use chrono::{NaiveDateTime, Utc};
+use diesel::dsl::now;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Deserialize, Serialize, Queryable, Identifiable)]
+#[table_name = "user_token"]
+pub struct UserToken {
+    pub id: i64,
+    pub author: i64,
+    pub name: String,
+    #[serde(skip_serializing)]
+    pub hash: String,
+    pub scopes: Vec<String>,
+    pub guild: Option<i64>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize, Insertable)]
+#[table_name = "user_token"]
+pub struct NewUserToken {
+    pub author: i64,
+    pub name: String,
+    pub hash: String,
+    pub scopes: Vec<String>,
+    pub guild: Option<i64>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    Read,
+    Queue,
+    Player,
+    Playlist,
+}
+
+impl FromStr for TokenScope {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl TokenScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Queue => "queue",
+            Self::Player => "player",
+            Self::Playlist => "playlist",
+        }
+    }
+}
+
+impl UserToken {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .map(|expires_at| expires_at <= Utc::now().naive_utc())
+            .unwrap_or(false)
+    }
+
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        scope == TokenScope::Read
+            || self
+                .scopes
+                .iter()
+                .any(|item| item.as_str() == scope.as_str())
+    }
+}
+
+impl Validate for NewUserToken {
+    fn check(&self) -> ApiResult<()> {
+        self.name.len().check_btw(
+            USER_TOKEN_NAME_MIN,
+            USER_TOKEN_NAME_MAX,
+            "field.name_length",
+        )?;
+
+        if self.scopes.is_empty()
+            || self
+                .scopes
+                .iter()
+                .any(|scope| scope.parse::<TokenScope>().is_err())
+        {
+            return Err(ApiResponse::bad_request()
+                .message("error.token_scopes_invalid")
+                .into());
+        }
+
+        check_duplicate(self.scopes.as_slice(), "field.token_scopes")?;
+
+        if let Some(expires_at) = self.expires_at {
+            if expires_at <= Utc::now().naive_utc() {
+                return Err(ApiResponse::bad_request()
+                    .message("error.token_expiry_invalid")
+                    .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+pub async fn create(pool: &PgPool, new_user_token: NewUserToken) -> ApiResult<UserToken> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<UserToken> {
+        let conn = pool.get()?;
+        let res = diesel::insert_into(user_token::table)
+            .values(new_user_token)
+            .get_result(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete(pool: &PgPool, id: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(user_token::table.find(id)).execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn delete_by_author(pool: &PgPool, author: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::delete(user_token::table.filter(user_token::author.eq(author)))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find(pool: &PgPool, id: i64) -> ApiResult<Option<UserToken>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<UserToken>> {
+        let conn = pool.get()?;
+        let res = user_token::table.find(id).first(&*conn).optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_hash(pool: &PgPool, hash: String) -> ApiResult<Option<UserToken>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Option<UserToken>> {
+        let conn = pool.get()?;
+        let res = user_token::table
+            .filter(user_token::hash.eq(hash))
+            .first(&*conn)
+            .optional()?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn find_by_author(pool: &PgPool, author: i64) -> ApiResult<Vec<UserToken>> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<Vec<UserToken>> {
+        let conn = pool.get()?;
+        let res = user_token::table
+            .filter(user_token::author.eq(author))
+            .order(user_token::created_at.desc())
+            .load(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
+
+pub async fn update_last_used(pool: &PgPool, id: i64) -> ApiResult<usize> {
+    let pool = pool.clone();
+
+    Ok(block(move || -> ApiResult<usize> {
+        let conn = pool.get()?;
+        let res = diesel::update(user_token::table.find(id))
+            .set(user_token::last_used_at.eq(now))
+            .execute(&*conn)?;
+
+        Ok(res)
+    })
+    .await?)
+}
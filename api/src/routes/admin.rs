@@ -1,12 +1,17 @@
+use crate::constants::{
+    FETCH_STAT_DAYS, FETCH_STAT_GUILDS, FETCH_STAT_RANGE_MAX, FETCH_STAT_TRACKS,
+};
 use crate::db::{cache, PgPool, RedisPool};
 use crate::models::blacklist::{self, EditBlacklist, NewBlacklist};
 use crate::models::guild::{self, Guild};
-use crate::models::{account, Validate};
+use crate::models::guild_stat::{self, GlobalDailyPlays, GuildPlays, TrackPlays};
+use crate::models::{account, Validate, ValidateExt};
 use crate::routes::{ApiResponse, ApiResult, OptionExt, ResultExt};
 use crate::utils::auth::User;
 
 use actix_web::web::{Data, Json, Path, Query};
 use actix_web::{delete, get, patch, put};
+use chrono::{Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -15,6 +20,18 @@ pub struct SimpleBlacklist {
     pub reason: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GlobalStats {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub plays: i64,
+    pub duration: i64,
+    pub hours: f64,
+    pub top_tracks: Vec<TrackPlays>,
+    pub top_guilds: Vec<GuildPlays>,
+    pub daily: Vec<GlobalDailyPlays>,
+}
+
 #[get("/guilds")]
 pub async fn get_guilds(
     user: User,
@@ -67,6 +84,49 @@ pub async fn get_top_guilds(
     ApiResponse::ok().data(guilds).finish()
 }
 
+#[get("/stats")]
+pub async fn get_stats(
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    user: User,
+    Query(mut query): Query<HashMap<String, String>>,
+) -> ApiResult<ApiResponse> {
+    user.has_bot_admin(&redis_pool).await?;
+
+    let to = query
+        .remove("to")
+        .map(|to| to.parse::<NaiveDate>().or_bad_request())
+        .transpose()?
+        .unwrap_or_else(|| Utc::now().naive_utc().date());
+    let from = query
+        .remove("from")
+        .map(|from| from.parse::<NaiveDate>().or_bad_request())
+        .transpose()?
+        .unwrap_or_else(|| to - Duration::days(FETCH_STAT_DAYS as i64 - 1));
+
+    (to - from)
+        .num_days()
+        .check_btw(0, FETCH_STAT_RANGE_MAX as i64 - 1, "field.days")?;
+
+    let daily = guild_stat::find_global_daily_stats(&pool, from, to).await?;
+    let duration = daily.iter().map(|day| day.duration).sum::<i64>();
+
+    let stats = GlobalStats {
+        from,
+        to,
+        plays: daily.iter().map(|day| day.plays).sum(),
+        duration,
+        hours: duration as f64 / 3_600_000.0,
+        top_tracks: guild_stat::find_global_track_stats(&pool, from, to, FETCH_STAT_TRACKS as i64)
+            .await?,
+        top_guilds: guild_stat::find_global_guild_stats(&pool, from, to, FETCH_STAT_GUILDS as i64)
+            .await?,
+        daily,
+    };
+
+    ApiResponse::ok().data(stats).finish()
+}
+
 #[get("/blacklist")]
 pub async fn get_blacklist(
     pool: Data<PgPool>,
@@ -104,7 +164,7 @@ pub async fn put_blacklist_item(
         .is_some()
     {
         return ApiResponse::bad_request()
-            .message("The user is already blacklisted.")
+            .message("error.blacklist_exists")
             .finish();
     }
 
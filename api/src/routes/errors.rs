@@ -1,4 +1,5 @@
-use crate::routes::{ApiResponse, ApiResult};
+use crate::routes::{ApiError, ApiResponse, ApiResult};
+use crate::utils::locale::get_language;
 
 use actix_web::body::Body;
 use actix_web::dev::ServiceResponse;
@@ -7,7 +8,17 @@ use actix_web::web::HttpResponse;
 use actix_web::Result;
 
 fn respond_error<B>(res: ServiceResponse<B>, response: ApiResponse) -> ErrorHandlerResponse<Body> {
-    let response = HttpResponse::build(response.status).json(response.data);
+    let language = get_language(res.request());
+    let body = match res
+        .response()
+        .error()
+        .and_then(|err| err.as_error::<ApiError>())
+    {
+        Some(err) => err.body(language.as_str()),
+        None => response.body(language.as_str()),
+    };
+
+    let response = HttpResponse::build(response.status).json(body);
 
     ErrorHandlerResponse::Response(res.into_response(response))
 }
@@ -17,7 +28,7 @@ pub fn bad_request<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<Bo
 }
 
 pub fn unauthorized<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<Body>> {
-    Ok(respond_error(res, ApiResponse::bad_request()))
+    Ok(respond_error(res, ApiResponse::unauthorized()))
 }
 
 pub fn forbidden<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<Body>> {
@@ -32,6 +43,10 @@ pub fn request_timeout<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerRespons
     Ok(respond_error(res, ApiResponse::request_timeout()))
 }
 
+pub fn payload_too_large<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<Body>> {
+    Ok(respond_error(res, ApiResponse::payload_too_large()))
+}
+
 pub fn internal_server_error<B>(res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<Body>> {
     Ok(respond_error(res, ApiResponse::internal_server_error()))
 }
@@ -1,34 +1,57 @@
-use crate::constants::{FETCH_STAT_TRACKS, FETCH_STAT_USERS};
+use crate::constants::{
+    FETCH_LOGS_EXPORT_MAX, FETCH_LOGS_MAX, FETCH_STAT_DAYS, FETCH_STAT_DAYS_MAX, FETCH_STAT_LIKES,
+    FETCH_STAT_TRACKS, FETCH_STAT_USERS,
+};
 use crate::db::pubsub::models::Guild;
 use crate::db::pubsub::Message;
 use crate::db::{cache, PgPool, RedisPool};
 use crate::models::config::EditConfig;
-use crate::models::{self, config, guild_log, guild_stat, playlist_item, Validate};
-use crate::routes::{ApiResponse, ApiResult, OptionExt};
+use crate::models::favourite::LikeStat;
+use crate::models::guild_log::{LogFilter, LogKind, LogOrigin};
+use crate::models::guild_permission::Permission;
+use crate::models::guild_stat::{DailyPlays, EndStats, TrackPlays, UserPlays};
+use crate::models::{
+    self, config, favourite, guild_log, guild_permission, guild_stat, guild_webhook, Validate,
+    ValidateExt,
+};
+use crate::routes::{ApiResponse, ApiResult, OptionExt, ResultExt};
 use crate::utils::auth::User;
-use crate::utils::log::{self, LogInfo};
+use crate::utils::locale::{get_language, Translation};
+use crate::utils::log::{self, LogFormat, LogInfo, RenderedLog};
 use crate::utils::player::get_player;
 use crate::utils::{self, polling};
 
-use actix_web::web::{Data, Json, Path};
-use actix_web::{delete, get, patch};
+use actix_web::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, HttpRequest, HttpResponse};
+use chrono::NaiveDate;
 use serde::Serialize;
 use std::collections::HashMap;
 use twilight_andesite::model::Destroy;
 use twilight_model::id::GuildId;
 
+pub mod permission;
 pub mod player;
 pub mod playlist;
 pub mod queue;
+pub mod webhook;
 
+pub use permission::*;
 pub use player::*;
 pub use playlist::*;
 pub use queue::*;
+pub use webhook::*;
 
 #[derive(Debug, Serialize)]
 pub struct GuildStats {
-    pub top_tracks: Vec<String>,
-    pub top_users: Vec<i64>,
+    pub days: u32,
+    pub top_tracks: Vec<TrackPlays>,
+    pub top_users: Vec<UserPlays>,
+    pub most_liked: Vec<LikeStat>,
+    pub daily: Vec<DailyPlays>,
+    pub ends: EndStats,
+    pub completion_rate: f64,
+    pub skip_rate: f64,
 }
 
 #[get("/{id}")]
@@ -54,6 +77,7 @@ pub async fn delete_guild(
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
 ) -> ApiResult<ApiResponse> {
+    user.is_session()?;
     user.has_read_guild(&redis_pool, id).await?;
 
     let guild: Guild = Message::get_guild(id)
@@ -70,14 +94,12 @@ pub async fn delete_guild(
         utils::queue::delete(&redis_pool, id).await?;
     }
 
-    let playlists = models::playlist::find_by_guild(&pool, id as i64).await?;
-    for playlist in playlists {
-        playlist_item::delete_by_playlist(&pool, playlist.id).await?;
-    }
-
     models::playlist::delete_by_guild(&pool, id as i64).await?;
     guild_stat::delete_by_guild(&pool, id as i64).await?;
     guild_log::delete_by_guild(&pool, id as i64).await?;
+    guild_webhook::delete_by_guild(&pool, id as i64).await?;
+    guild_permission::delete_by_guild(&pool, id as i64).await?;
+    cache::invalidate_guild_permissions(&redis_pool, id).await?;
     config::delete(&pool, id as i64).await?;
 
     ApiResponse::ok().finish()
@@ -102,38 +124,48 @@ pub async fn get_guild_stats(
     pool: Data<PgPool>,
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
+    Query(mut query): Query<HashMap<String, String>>,
 ) -> ApiResult<ApiResponse> {
     user.has_read_guild(&redis_pool, id).await?;
 
-    let stats = guild_stat::find_by_guild(&pool, id as i64).await?;
-    let mut top_tracks = HashMap::new();
-    let mut top_users = HashMap::new();
-
-    for stat in stats {
-        let track_counter = top_tracks.entry(stat.title).or_insert(0);
-        *track_counter += 1;
-
-        let user_counter = top_users.entry(stat.author).or_insert(0);
-        *user_counter += 1;
-    }
-
-    let mut top_tracks = top_tracks.into_iter().collect::<Vec<(String, i32)>>();
-    top_tracks.sort_by(|a, b| a.1.cmp(&b.1));
-
-    let mut top_users = top_users.into_iter().collect::<Vec<(i64, i32)>>();
-    top_users.sort_by(|a, b| a.1.cmp(&b.1));
+    let days = query
+        .remove("days")
+        .map(|days| days.parse::<u32>().or_bad_request())
+        .transpose()?
+        .unwrap_or(FETCH_STAT_DAYS as u32);
+
+    days.check_btw(1, FETCH_STAT_DAYS_MAX as u32, "field.days")?;
+
+    let ends = guild_stat::find_end_stats(&pool, id as i64, days as i32).await?;
+    let rate = |amount: i64| {
+        if ends.ended > 0 {
+            amount as f64 / ends.ended as f64
+        } else {
+            0.0
+        }
+    };
 
     let guild_stats = GuildStats {
-        top_tracks: top_tracks
-            .into_iter()
-            .take(FETCH_STAT_TRACKS)
-            .map(|(k, _)| k)
-            .collect(),
-        top_users: top_users
-            .into_iter()
-            .take(FETCH_STAT_USERS)
-            .map(|(k, _)| k)
-            .collect(),
+        days,
+        top_tracks: guild_stat::find_track_stats(
+            &pool,
+            id as i64,
+            days as i32,
+            FETCH_STAT_TRACKS as i64,
+        )
+        .await?,
+        top_users: guild_stat::find_user_stats(
+            &pool,
+            id as i64,
+            days as i32,
+            FETCH_STAT_USERS as i64,
+        )
+        .await?,
+        most_liked: favourite::find_most_liked(&pool, id as i64, FETCH_STAT_LIKES as i64).await?,
+        daily: guild_stat::find_daily_stats(&pool, id as i64, days as i32).await?,
+        completion_rate: rate(ends.finished),
+        skip_rate: rate(ends.skipped),
+        ends,
     };
 
     ApiResponse::ok().data(guild_stats).finish()
@@ -146,6 +178,7 @@ pub async fn delete_guild_stats(
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
 ) -> ApiResult<ApiResponse> {
+    user.is_session()?;
     user.has_read_guild(&redis_pool, id).await?;
 
     let guild: Guild = Message::get_guild(id)
@@ -184,7 +217,8 @@ pub async fn patch_guild_settings(
     Path(id): Path<u64>,
     Json(new_settings): Json<EditConfig>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_guild(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
 
     new_settings.check()?;
 
@@ -193,20 +227,6 @@ pub async fn patch_guild_settings(
         .await?
         .or_not_found()?;
 
-    let mut roles = vec![];
-    roles.extend(new_settings.track_roles.as_deref());
-    roles.extend(new_settings.player_roles.as_deref());
-    roles.extend(new_settings.queue_roles.as_deref());
-    roles.extend(new_settings.playlist_roles.as_deref());
-    roles.extend(new_settings.guild_roles.as_deref());
-
-    let invalid_roles: Vec<String> = roles
-        .into_iter()
-        .flatten()
-        .filter(|role| guild.roles.iter().all(|guild_role| **role != guild_role.id))
-        .map(|role| role.to_string())
-        .collect();
-
     let channels = vec![
         new_settings.playing_log.unwrap_or_default(),
         new_settings.player_log.unwrap_or_default(),
@@ -225,18 +245,17 @@ pub async fn patch_guild_settings(
         .map(|channel| channel.to_string())
         .collect();
 
-    if !invalid_roles.is_empty() {
-        return ApiResponse::bad_request()
-            .message(format!("Invalid roles: {}", invalid_roles.join(", ")).as_str())
-            .finish();
-    }
-
     if !invalid_channels.is_empty() {
         return ApiResponse::bad_request()
-            .message(format!("Invalid channels: {}", invalid_channels.join(", ")).as_str())
+            .message(
+                Translation::new("error.channels_invalid")
+                    .arg("channels", invalid_channels.join(", ")),
+            )
             .finish();
     }
 
+    let settings = cache::get_config(&pool, &redis_pool, id).await?;
+
     config::update(&pool, id as i64, new_settings.clone()).await?;
     cache::invalidate_config(&pool, &redis_pool, id).await?;
 
@@ -245,23 +264,137 @@ pub async fn patch_guild_settings(
         &redis_pool,
         id,
         user,
-        LogInfo::SettingsUpdate(new_settings),
+        LogInfo::SettingsUpdate(settings, new_settings),
     )
     .await?;
 
     ApiResponse::ok().finish()
 }
 
+async fn get_log_filter(
+    pool: &PgPool,
+    guild: u64,
+    query: &mut HashMap<String, String>,
+) -> ApiResult<LogFilter> {
+    let author = query
+        .remove("author")
+        .map(|author| author.parse::<u64>().or_bad_request())
+        .transpose()?;
+    let kind = query
+        .remove("kind")
+        .map(|kind| kind.parse::<LogKind>().or_bad_request())
+        .transpose()?;
+    let origin = query
+        .remove("origin")
+        .map(|origin| origin.parse::<LogOrigin>().or_bad_request())
+        .transpose()?;
+    let from = query
+        .remove("from")
+        .map(|from| from.parse::<NaiveDate>().or_bad_request())
+        .transpose()?;
+    let to = query
+        .remove("to")
+        .map(|to| to.parse::<NaiveDate>().or_bad_request())
+        .transpose()?;
+
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            return Err(ApiResponse::bad_request()
+                .message("error.date_range_invalid")
+                .into());
+        }
+    }
+
+    let before = match query.remove("before") {
+        Some(before) => {
+            let before = before.parse::<u64>().or_bad_request()?;
+            let log = guild_log::find(pool, before as i64)
+                .await?
+                .filter(|log| log.guild == guild as i64)
+                .or_bad_request()?;
+            Some(log)
+        },
+        None => None,
+    };
+
+    Ok(LogFilter {
+        author: author.map(|author| author as i64),
+        kind,
+        origin,
+        from,
+        to,
+        before,
+    })
+}
+
 #[get("/{id}/logs")]
 pub async fn get_guild_logs(
+    req: HttpRequest,
     user: User,
     pool: Data<PgPool>,
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
+    Query(mut query): Query<HashMap<String, String>>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_guild(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    let filter = get_log_filter(&pool, id, &mut query).await?;
+    let limit = query
+        .remove("limit")
+        .map(|limit| limit.parse::<u32>().or_bad_request())
+        .transpose()?
+        .unwrap_or(FETCH_LOGS_MAX as u32);
+
+    if limit == 0 || limit as usize > FETCH_LOGS_MAX {
+        return Err(ApiResponse::bad_request()
+            .message("error.log_fetch_invalid")
+            .into());
+    }
 
-    let logs = guild_log::find_by_guild(&pool, id as i64).await?;
+    let language = get_language(&req);
+    let logs: Vec<RenderedLog> = guild_log::find_by_guild(&pool, id as i64, filter, limit as i64)
+        .await?
+        .into_iter()
+        .map(|log| RenderedLog::new(log, language.as_str()))
+        .collect();
 
     ApiResponse::ok().data(logs).finish()
 }
+
+#[get("/{id}/logs/export")]
+pub async fn get_guild_logs_export(
+    req: HttpRequest,
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+    Query(mut query): Query<HashMap<String, String>>,
+) -> ApiResult<HttpResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    let format = query
+        .remove("format")
+        .map(|format| format.parse::<LogFormat>().or_bad_request())
+        .transpose()?
+        .unwrap_or(LogFormat::Json);
+
+    let filter = get_log_filter(&pool, id, &mut query).await?;
+    let logs =
+        guild_log::find_by_guild(&pool, id as i64, filter, FETCH_LOGS_EXPORT_MAX as i64).await?;
+
+    let content = log::export(format, logs, get_language(&req).as_str())?;
+
+    Ok(HttpResponse::Ok()
+        .header(CONTENT_TYPE, format.content_type())
+        .header(
+            CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"logs-{}.{}\"",
+                id,
+                format.extension()
+            ),
+        )
+        .body(content))
+}
@@ -0,0 +1,119 @@
+use crate::constants::GUILD_PERMISSION_MAX;
+use crate::db::pubsub::models::{Guild, Member};
+use crate::db::pubsub::Message;
+use crate::db::{cache, PgPool, RedisPool};
+use crate::models::guild_permission::{self, NewGuildPermission, Permission, PermissionKind};
+use crate::models::Validate;
+use crate::routes::{ApiResponse, ApiResult, OptionExt, ResultExt};
+use crate::utils::auth::User;
+
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, put};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SimpleGuildPermission {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+#[get("/{id}/permissions")]
+pub async fn get_guild_permissions(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    let permissions = guild_permission::find_by_guild(&pool, id as i64).await?;
+
+    ApiResponse::ok().data(permissions).finish()
+}
+
+#[put("/{id}/permissions/{kind}/{target}")]
+pub async fn put_guild_permission(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, kind, target)): Path<(u64, String, u64)>,
+    Json(permission): Json<SimpleGuildPermission>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    let new_permission = NewGuildPermission {
+        guild: id as i64,
+        kind,
+        target: target as i64,
+        allow: permission.allow,
+        deny: permission.deny,
+    };
+
+    new_permission.check()?;
+
+    let exists = match new_permission
+        .kind
+        .parse::<PermissionKind>()
+        .or_bad_request()?
+    {
+        PermissionKind::Role => {
+            let guild: Guild = Message::get_guild(id)
+                .send_and_wait(&redis_pool)
+                .await?
+                .or_not_found()?;
+
+            guild.roles.iter().any(|role| role.id == target as i64)
+        },
+        PermissionKind::User => Message::get_member(id, target)
+            .send_and_wait::<Member>(&redis_pool)
+            .await?
+            .is_some(),
+    };
+
+    if !exists {
+        return ApiResponse::bad_request()
+            .message("error.permission_target_invalid")
+            .finish();
+    }
+
+    let permissions = guild_permission::find_by_guild(&pool, id as i64).await?;
+
+    if permissions.len() >= GUILD_PERMISSION_MAX
+        && permissions
+            .iter()
+            .all(|item| item.kind != new_permission.kind || item.target != new_permission.target)
+    {
+        return ApiResponse::bad_request()
+            .message("error.permission_limit")
+            .finish();
+    }
+
+    let permission = guild_permission::create(&pool, new_permission).await?;
+    cache::invalidate_guild_permissions(&redis_pool, id).await?;
+
+    ApiResponse::ok().data(permission).finish()
+}
+
+#[delete("/{id}/permissions/{kind}/{target}")]
+pub async fn delete_guild_permission(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, kind, target)): Path<(u64, String, u64)>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    guild_permission::find(&pool, id as i64, kind.clone(), target as i64)
+        .await?
+        .or_not_found()?;
+
+    guild_permission::delete(&pool, id as i64, kind, target as i64).await?;
+    cache::invalidate_guild_permissions(&redis_pool, id).await?;
+
+    ApiResponse::ok().finish()
+}
@@ -1,5 +1,5 @@
 use crate::constants::{
-    FILTER_EQUALIZER_BAND_MAX, FILTER_EQUALIZER_BAND_MIN, FILTER_EQUALIZER_GAIN_MAX,
+    FAVOURITE_MAX, FILTER_EQUALIZER_BAND_MAX, FILTER_EQUALIZER_BAND_MIN, FILTER_EQUALIZER_GAIN_MAX,
     FILTER_EQUALIZER_GAIN_MIN, FILTER_KARAOKE_BAND_MAX, FILTER_KARAOKE_BAND_MIN,
     FILTER_KARAOKE_LEVEL_MAX, FILTER_KARAOKE_LEVEL_MIN, FILTER_KARAOKE_MONO_LEVEL_MAX,
     FILTER_KARAOKE_MONO_LEVEL_MIN, FILTER_KARAOKE_WIDTH_MAX, FILTER_KARAOKE_WIDTH_MIN,
@@ -12,6 +12,9 @@ use crate::constants::{
 use crate::db::pubsub::models::{self, Connected};
 use crate::db::pubsub::Message;
 use crate::db::{PgPool, RedisPool};
+use crate::models::favourite::{self, NewFavourite};
+use crate::models::guild_permission::Permission;
+use crate::models::user_token::TokenScope;
 use crate::models::{Validate, ValidateExt};
 use crate::routes::{ApiResponse, ApiResult};
 use crate::utils::auth::User;
@@ -40,7 +43,7 @@ pub struct SimplePlayer {
 impl Validate for SimplePlayer {
     fn check(&self) -> ApiResult<()> {
         if let Some(volume) = self.volume {
-            (volume as usize).check_max(VOLUME_MAX, "volume")?;
+            (volume as usize).check_max(VOLUME_MAX, "field.volume")?;
         }
 
         if let Some(filters) = &self.filters {
@@ -49,12 +52,12 @@ impl Validate for SimplePlayer {
                     band.band.check_btw(
                         FILTER_EQUALIZER_BAND_MIN as i64,
                         FILTER_EQUALIZER_BAND_MAX as i64,
-                        "equalizer band",
+                        "field.equalizer_band",
                     )?;
                     band.gain.check_btw(
                         FILTER_EQUALIZER_GAIN_MIN,
                         FILTER_EQUALIZER_GAIN_MAX,
-                        "equalizer band gain",
+                        "field.equalizer_band_gain",
                     )?;
                 }
             }
@@ -62,63 +65,63 @@ impl Validate for SimplePlayer {
                 timescale.speed.check_btw(
                     FILTER_TIMESCALE_SPEED_MIN,
                     FILTER_TIMESCALE_SPEED_MAX,
-                    "timescale speed",
+                    "field.timescale_speed",
                 )?;
                 timescale.pitch.check_btw(
                     FILTER_TIMESCALE_PITCH_MIN,
                     FILTER_TIMESCALE_PITCH_MAX,
-                    "timescale pitch",
+                    "field.timescale_pitch",
                 )?;
                 timescale.rate.check_btw(
                     FILTER_TIMESCALE_RATE_MIN,
                     FILTER_TIMESCALE_RATE_MAX,
-                    "timescale rate",
+                    "field.timescale_rate",
                 )?;
             }
             if let Some(tremolo) = &filters.tremolo {
                 tremolo.depth.check_btw(
                     FILTER_TREMOLO_DEPTH_MIN,
                     FILTER_TREMOLO_DEPTH_MAX,
-                    "tremolo depth",
+                    "field.tremolo_depth",
                 )?;
                 tremolo.frequency.check_btw(
                     FILTER_TREMOLO_FREQUENCY_MIN,
                     FILTER_TREMOLO_FREQUENCY_MAX,
-                    "tremolo frequency",
+                    "field.tremolo_frequency",
                 )?;
             }
             if let Some(vibrato) = &filters.vibrato {
                 vibrato.depth.check_btw(
                     FILTER_VIBRATO_DEPTH_MIN,
                     FILTER_VIBRATO_DEPTH_MAX,
-                    "vibrato depth",
+                    "field.vibrato_depth",
                 )?;
                 vibrato.frequency.check_btw(
                     FILTER_VIBRATO_FREQUENCY_MIN,
                     FILTER_VIBRATO_FREQUENCY_MAX,
-                    "vibrato frequency",
+                    "field.vibrato_frequency",
                 )?;
             }
             if let Some(karaoke) = &filters.karaoke {
                 karaoke.level.check_btw(
                     FILTER_KARAOKE_LEVEL_MIN,
                     FILTER_KARAOKE_LEVEL_MAX,
-                    "karaoke level",
+                    "field.karaoke_level",
                 )?;
                 karaoke.mono_level.check_btw(
                     FILTER_KARAOKE_MONO_LEVEL_MIN,
                     FILTER_KARAOKE_MONO_LEVEL_MAX,
-                    "karaoke mono level",
+                    "field.karaoke_mono_level",
                 )?;
                 karaoke.filter_band.check_btw(
                     FILTER_KARAOKE_BAND_MIN,
                     FILTER_KARAOKE_BAND_MAX,
-                    "karaoke band",
+                    "field.karaoke_band",
                 )?;
                 karaoke.filter_width.check_btw(
                     FILTER_KARAOKE_WIDTH_MIN,
                     FILTER_KARAOKE_WIDTH_MAX,
-                    "karaoke width",
+                    "field.karaoke_width",
                 )?;
             }
         }
@@ -181,6 +184,7 @@ pub async fn post_guild_player(
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
 ) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Player, Some(id))?;
     user.has_read_guild(&redis_pool, id).await?;
 
     let connected: Option<Connected> = Message::get_connected(id, None)
@@ -190,7 +194,7 @@ pub async fn post_guild_player(
     if let Some(connected) = connected {
         if connected.members.len() > 1 {
             return ApiResponse::bad_request()
-                .message("The bot is already in another channel.")
+                .message("error.bot_other_channel")
                 .finish();
         }
     }
@@ -198,9 +202,7 @@ pub async fn post_guild_player(
     let user_connected: models::Connected = Message::get_connected(id, user.user.id as u64)
         .send_and_wait(&redis_pool)
         .await?
-        .ok_or_else(|| {
-            ApiResponse::bad_request().message("You need to be connected to a channel.")
-        })?;
+        .ok_or_else(|| ApiResponse::bad_request().message("error.not_connected"))?;
 
     Message::set_connected(id, user_connected.channel as u64)
         .send_and_pause(&redis_pool)
@@ -228,6 +230,7 @@ pub async fn patch_guild_player(
     Path(id): Path<u64>,
     Json(mut new_player): Json<SimplePlayer>,
 ) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Player, Some(id))?;
     user.has_read_guild(&redis_pool, id).await?;
     user.is_connected(&redis_pool, id, true).await?;
 
@@ -247,12 +250,27 @@ pub async fn patch_guild_player(
         }
     }
 
-    user.has_manage_player(&pool, &redis_pool, id).await?;
+    let permissions = user.get_permissions(&pool, &redis_pool, id).await?;
+    let required = [
+        (new_player.looping.is_some(), Permission::Loop),
+        (new_player.playing.is_some(), Permission::Skip),
+        (new_player.position.is_some(), Permission::Seek),
+        (new_player.paused.is_some(), Permission::Pause),
+        (new_player.volume.is_some(), Permission::Volume),
+        (new_player.filters.is_some(), Permission::Filters),
+    ];
+
+    if required
+        .iter()
+        .any(|(update, permission)| *update && !permissions.contains(permission))
+    {
+        return ApiResponse::forbidden().finish();
+    }
 
     if let Some(playing) = new_player.playing {
         if playing >= queue::len(&redis_pool, id).await? as i32 || playing < -1 {
             return ApiResponse::bad_request()
-                .message("The requested track to play does not exist.")
+                .message("error.playing_not_found")
                 .finish();
         }
 
@@ -304,7 +322,8 @@ pub async fn delete_guild_player(
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_player(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::Disconnect)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
     let connected: Option<Connected> = Message::get_connected(id, None)
@@ -330,3 +349,39 @@ pub async fn delete_guild_player(
 
     ApiResponse::ok().finish()
 }
+
+#[post("/{id}/player/like")]
+pub async fn post_guild_player_like(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, Some(id))?;
+    user.has_read_guild(&redis_pool, id).await?;
+
+    let track = queue::get_playing_track(&redis_pool, id)
+        .await?
+        .ok_or_else(|| ApiResponse::bad_request().message("error.no_playing"))?;
+
+    if favourite::count_by_author(&pool, user.user.id).await? as usize >= FAVOURITE_MAX {
+        return ApiResponse::bad_request()
+            .message("error.favourite_limit")
+            .finish();
+    }
+
+    let new_favourite = NewFavourite {
+        author: user.user.id,
+        guild: id as i64,
+        track: track.track,
+        title: track.title,
+        uri: track.uri,
+        length: track.length,
+    };
+
+    let favourite = favourite::create(&pool, new_favourite)
+        .await?
+        .ok_or_else(|| ApiResponse::bad_request().message("error.favourite_exists"))?;
+
+    ApiResponse::ok().data(favourite).finish()
+}
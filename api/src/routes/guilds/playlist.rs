@@ -1,32 +1,81 @@
-use crate::constants::PLAYLIST_MAX;
+use crate::constants::{
+    FETCH_PLAYLIST_ITEMS_MAX, PLAYLIST_IMPORT_CONCURRENCY, PLAYLIST_IMPORT_MAX, PLAYLIST_ITEM_MAX,
+    PLAYLIST_MAX,
+};
 use crate::db::{cache, PgPool, RedisPool};
-use crate::models::playlist::{self, EditPlaylist, NewPlaylist};
-use crate::models::playlist_item::{self, NewPlaylistItem, PlaylistItem};
-use crate::models::Validate;
-use crate::routes::{ApiResponse, ApiResult, OptionExt};
+use crate::models::guild_permission::Permission;
+use crate::models::playlist::{self, EditPlaylist, NewPlaylist, NewSmartPlaylist};
+use crate::models::playlist_item::{self, NewPlaylistItem};
+use crate::models::{string_int_opt, Validate};
+use crate::routes::guilds::SimplePosition;
+use crate::routes::{get_page, ApiResponse, ApiResult, OptionExt, ResultExt};
 use crate::utils::auth::User;
 use crate::utils::log::{self, LogInfo};
-use crate::utils::polling;
+use crate::utils::playlist::{ExportItem, PlaylistFormat};
 use crate::utils::queue::{self, QueueItem};
-
-use actix_web::web::{Data, Json, Path};
-use actix_web::{delete, get, patch, post};
-use chrono::NaiveDateTime;
+use crate::utils::{self, polling};
+
+use actix_web::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, put, HttpResponse};
+use futures::{stream, StreamExt};
+use rand::seq::SliceRandom;
+use rand::thread_rng;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Deserialize)]
 pub struct SimplePlaylist {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SimpleSmartPlaylist {
+    pub name: String,
+    #[serde(default, deserialize_with = "string_int_opt")]
+    pub author: Option<u64>,
+    pub days: u32,
+    pub amount: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimplePlaylistItems {
+    pub tracks: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadMode {
+    Append,
+    Next,
+    Replace,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SimplePlaylistLoad {
+    #[serde(default)]
+    pub shuffle: bool,
+    pub mode: Option<LoadMode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlaylistLoad {
+    pub added: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SimplePlaylistImport {
+    pub name: Option<String>,
+    pub format: PlaylistFormat,
+    pub content: String,
+}
+
 #[derive(Debug, Serialize)]
-pub struct FullPlaylist {
+pub struct PlaylistImport {
     pub id: i64,
-    pub guild: i64,
-    pub name: String,
-    pub author: i64,
-    pub created_at: NaiveDateTime,
-    pub items: Vec<PlaylistItem>,
+    pub imported: usize,
+    pub failed: Vec<ExportItem>,
 }
 
 #[get("/{id}/playlists")]
@@ -38,22 +87,46 @@ pub async fn get_guild_playlists(
 ) -> ApiResult<ApiResponse> {
     user.has_read_guild(&redis_pool, id).await?;
 
-    let mut playlists = vec![];
-    for playlist in playlist::find_by_guild(&pool, id as i64).await? {
-        let playlist = FullPlaylist {
-            id: playlist.id,
-            guild: playlist.guild,
-            name: playlist.name,
-            author: playlist.author,
-            created_at: playlist.created_at,
-            items: playlist_item::find_by_playlist(&pool, playlist.id).await?,
-        };
-        playlists.push(playlist);
-    }
+    let playlists = utils::playlist::get_summaries(&pool, id as i64).await?;
 
     ApiResponse::ok().data(playlists).finish()
 }
 
+#[get("/{id}/playlists/{item}/items")]
+pub async fn get_guild_playlist_items(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, item)): Path<(u64, u64)>,
+    Query(mut query): Query<HashMap<String, String>>,
+) -> ApiResult<ApiResponse> {
+    user.has_read_guild(&redis_pool, id).await?;
+
+    let (offset, limit) = get_page(
+        &mut query,
+        FETCH_PLAYLIST_ITEMS_MAX,
+        "error.track_fetch_invalid",
+    )?;
+
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
+
+    let items = if playlist.smart {
+        utils::playlist::get_items(&pool, &playlist)
+            .await?
+            .into_iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    } else {
+        playlist_item::find_page_by_playlist(&pool, item as i64, offset, limit).await?
+    };
+
+    ApiResponse::ok().data(items).finish()
+}
+
 #[post("/{id}/playlists")]
 pub async fn post_guild_playlists(
     user: User,
@@ -62,12 +135,13 @@ pub async fn post_guild_playlists(
     Path(id): Path<u64>,
     Json(new_playlist): Json<SimplePlaylist>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_playlist(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
 
     let playlists = playlist::find_by_guild(&pool, id as i64).await?;
     let tracks = queue::get(&redis_pool, id).await?;
     let new_playlist = NewPlaylist {
-        guild: id as i64,
+        guild: Some(id as i64),
         author: user.user.id,
         name: new_playlist.name,
     };
@@ -76,7 +150,7 @@ pub async fn post_guild_playlists(
 
     if playlists.len() >= PLAYLIST_MAX {
         return ApiResponse::bad_request()
-            .message("This server has reached the maximum number of playlists.")
+            .message("error.playlist_limit")
             .finish();
     }
 
@@ -85,28 +159,30 @@ pub async fn post_guild_playlists(
         .any(|playlist| playlist.name.to_lowercase() == new_playlist.name.to_lowercase())
     {
         return ApiResponse::bad_request()
-            .message("A playlist with the same name already exists.")
+            .message("error.playlist_exists")
             .finish();
     }
 
     if tracks.is_empty() {
         return ApiResponse::bad_request()
-            .message("There are no tracks in the queue currently.")
+            .message("error.queue_empty")
             .finish();
     }
 
-    let playlist = playlist::create(&pool, new_playlist.clone()).await?;
-
-    for track in tracks {
-        let item = NewPlaylistItem {
-            playlist: playlist.id,
+    let items = tracks
+        .into_iter()
+        .enumerate()
+        .map(|(index, track)| NewPlaylistItem {
+            playlist: 0,
             track: track.track,
             title: track.title,
             uri: track.uri,
             length: track.length,
-        };
-        playlist_item::create(&pool, item).await?;
-    }
+            position: index as i32,
+        })
+        .collect();
+
+    playlist::create(&pool, new_playlist.clone(), items).await?;
 
     log::register(
         &pool,
@@ -120,6 +196,63 @@ pub async fn post_guild_playlists(
     ApiResponse::ok().finish()
 }
 
+#[post("/{id}/playlists/smart")]
+pub async fn post_guild_playlists_smart(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+    Json(new_playlist): Json<SimpleSmartPlaylist>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
+
+    let playlists = playlist::find_by_guild(&pool, id as i64).await?;
+    let new_playlist = NewSmartPlaylist {
+        guild: id as i64,
+        author: user.user.id,
+        name: new_playlist.name,
+        smart: true,
+        smart_author: new_playlist.author.unwrap_or_default() as i64,
+        smart_days: new_playlist.days as i32,
+        smart_amount: new_playlist.amount as i32,
+    };
+
+    new_playlist.check()?;
+
+    if playlists.len() >= PLAYLIST_MAX {
+        return ApiResponse::bad_request()
+            .message("error.playlist_limit")
+            .finish();
+    }
+
+    if playlists
+        .iter()
+        .any(|playlist| playlist.name.to_lowercase() == new_playlist.name.to_lowercase())
+    {
+        return ApiResponse::bad_request()
+            .message("error.playlist_exists")
+            .finish();
+    }
+
+    let playlist = playlist::create_smart(&pool, new_playlist.clone()).await?;
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::PlaylistAdd(NewPlaylist {
+            guild: Some(new_playlist.guild),
+            name: new_playlist.name,
+            author: new_playlist.author,
+        }),
+    )
+    .await?;
+
+    ApiResponse::ok().data(playlist).finish()
+}
+
 #[patch("/{id}/playlists/{item}")]
 pub async fn patch_guild_playlist(
     user: User,
@@ -128,9 +261,13 @@ pub async fn patch_guild_playlist(
     Path((id, item)): Path<(u64, u64)>,
     Json(new_playlist): Json<EditPlaylist>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_playlist(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
 
-    playlist::find(&pool, item as i64).await?;
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
     new_playlist.check()?;
 
     if let Some(name) = &new_playlist.name {
@@ -140,7 +277,7 @@ pub async fn patch_guild_playlist(
             .any(|playlist| playlist.name.to_lowercase().as_str() == name)
         {
             return ApiResponse::bad_request()
-                .message("A playlist with the same name already exists.")
+                .message("error.playlist_exists")
                 .finish();
         }
     }
@@ -152,7 +289,7 @@ pub async fn patch_guild_playlist(
         &redis_pool,
         id,
         user,
-        LogInfo::PlaylistUpdate(new_playlist),
+        LogInfo::PlaylistUpdate(playlist, new_playlist),
     )
     .await?;
 
@@ -166,10 +303,13 @@ pub async fn delete_guild_playlist(
     redis_pool: Data<RedisPool>,
     Path((id, item)): Path<(u64, u64)>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_playlist(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
 
-    let playlist = playlist::find(&pool, item as i64).await?.or_not_found()?;
-    playlist_item::delete_by_playlist(&pool, item as i64).await?;
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
     playlist::delete(&pool, item as i64).await?;
 
     log::register(
@@ -190,45 +330,88 @@ pub async fn post_guild_playlist_load(
     pool: Data<PgPool>,
     redis_pool: Data<RedisPool>,
     Path((id, item)): Path<(u64, u64)>,
+    options: Option<Json<SimplePlaylistLoad>>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_track(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::LoadPlaylist)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
-    let playlist = playlist::find(&pool, item as i64).await?.or_not_found()?;
-    let tracks = playlist_item::find_by_playlist(&pool, item as i64).await?;
+    let options = options
+        .map(|options| options.into_inner())
+        .unwrap_or_default();
+    let mode = options.mode.unwrap_or(LoadMode::Append);
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| {
+            playlist.guild == Some(id as i64)
+                || (playlist.is_personal() && playlist.author == user.user.id)
+        })
+        .or_not_found()?;
     let config = cache::get_config(&pool, &redis_pool, id).await?;
+    let mut tracks: Vec<QueueItem> = utils::playlist::get_items(&pool, &playlist)
+        .await?
+        .into_iter()
+        .map(|track| QueueItem {
+            track: track.track,
+            title: track.title,
+            uri: track.uri,
+            length: track.length,
+            author: user.user.id,
+            username: user.user.username.clone(),
+            discriminator: user.user.discriminator,
+        })
+        .collect();
+
+    let mut queue = match mode {
+        LoadMode::Replace => vec![],
+        LoadMode::Append | LoadMode::Next => queue::get(&redis_pool, id).await?,
+    };
     let amount = tracks.len();
 
-    if config.max_queue as usize - queue::len(&redis_pool, id).await? < amount {
+    if tracks.is_empty() {
         return ApiResponse::bad_request()
-            .message("The queue is already at maximum length.")
+            .message("error.playlist_empty")
             .finish();
     }
 
-    for track in tracks {
-        if config.no_duplicate
-            && queue::get(&redis_pool, id)
-                .await?
-                .iter()
-                .any(|item| item.track == track.track)
-        {
-            continue;
-        }
+    if options.shuffle {
+        tracks.shuffle(&mut thread_rng());
+    }
 
-        queue::add(
-            &redis_pool,
-            id,
-            QueueItem {
-                track: track.track,
-                title: track.title,
-                uri: track.uri,
-                length: track.length,
-                author: user.user.id,
-                username: user.user.username.clone(),
-                discriminator: user.user.discriminator,
-            },
-        )
-        .await?;
+    if config.no_duplicate {
+        let mut existing: HashSet<String> = queue.iter().map(|item| item.track.clone()).collect();
+        tracks.retain(|track| existing.insert(track.track.clone()));
+    }
+
+    let load = PlaylistLoad {
+        added: tracks.len(),
+        skipped: amount - tracks.len(),
+    };
+
+    if queue.len() + tracks.len() > config.max_queue as usize {
+        return ApiResponse::bad_request()
+            .message("error.queue_full")
+            .finish();
+    }
+
+    match mode {
+        LoadMode::Append | LoadMode::Replace => queue.extend(tracks),
+        LoadMode::Next => {
+            let playing = queue::get_playing(&redis_pool, id).await?;
+            let position = if playing >= 0 {
+                (playing as usize + 1).min(queue.len())
+            } else {
+                queue.len()
+            };
+            queue.splice(position..position, tracks);
+        },
+    }
+
+    queue::set(&redis_pool, id, queue).await?;
+
+    if let LoadMode::Replace = mode {
+        queue::set_playing(&redis_pool, id, 0).await?;
+        queue::play(&redis_pool, id).await?;
     }
 
     log::register(
@@ -236,11 +419,334 @@ pub async fn post_guild_playlist_load(
         &redis_pool,
         id,
         user,
-        LogInfo::PlaylistLoad(playlist, amount as u64),
+        LogInfo::PlaylistLoad(playlist, load.added as u64),
     )
     .await?;
 
     polling::notify(id)?;
 
+    ApiResponse::ok().data(load).finish()
+}
+
+#[post("/{id}/playlists/{item}/items")]
+pub async fn post_guild_playlist_items(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, item)): Path<(u64, u64)>,
+    Json(new_items): Json<SimplePlaylistItems>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
+
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
+
+    if playlist.smart {
+        return ApiResponse::bad_request()
+            .message("error.playlist_smart")
+            .finish();
+    }
+
+    if new_items.tracks.is_empty() {
+        return ApiResponse::bad_request()
+            .message("error.playlist_add_empty")
+            .finish();
+    }
+
+    let items = utils::playlist::decode_tracks(new_items.tracks.as_slice())
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(index, track)| NewPlaylistItem {
+            playlist: playlist.id,
+            track: track.track,
+            title: track.info.title,
+            uri: track.info.uri,
+            length: track.info.length as i32,
+            position: index as i32,
+        })
+        .collect();
+
+    let added = playlist_item::batch_create(&pool, playlist.id, items, PLAYLIST_ITEM_MAX).await?;
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::PlaylistItemAdd(playlist, added as u64),
+    )
+    .await?;
+
     ApiResponse::ok().finish()
 }
+
+#[post("/{id}/playlists/{item}/items/playing")]
+pub async fn post_guild_playlist_items_playing(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, item)): Path<(u64, u64)>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
+
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
+
+    if playlist.smart {
+        return ApiResponse::bad_request()
+            .message("error.playlist_smart")
+            .finish();
+    }
+
+    let track = queue::get_playing_track(&redis_pool, id)
+        .await?
+        .ok_or_else(|| ApiResponse::bad_request().message("error.no_playing"))?;
+
+    let new_item = NewPlaylistItem {
+        playlist: playlist.id,
+        track: track.track,
+        title: track.title,
+        uri: track.uri,
+        length: track.length,
+        position: 0,
+    };
+
+    playlist_item::create(&pool, new_item, PLAYLIST_ITEM_MAX).await?;
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::PlaylistItemAdd(playlist, 1),
+    )
+    .await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[put("/{id}/playlists/{item}/items/{track}/position")]
+pub async fn put_guild_playlist_item_position(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, item, track)): Path<(u64, u64, u64)>,
+    Json(new_position): Json<SimplePosition>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
+
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
+    let playlist_track = playlist_item::find(&pool, track as i64)
+        .await?
+        .filter(|playlist_track| playlist_track.playlist == playlist.id)
+        .or_not_found()?;
+
+    if playlist_item::count_by_playlist(&pool, item as i64).await? <= new_position.position as i64 {
+        return ApiResponse::bad_request()
+            .message("error.position_invalid")
+            .finish();
+    }
+
+    playlist_item::shift(&pool, track as i64, new_position.position as i32).await?;
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::PlaylistItemShift(playlist, playlist_track, new_position),
+    )
+    .await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[delete("/{id}/playlists/{item}/items/{track}")]
+pub async fn delete_guild_playlist_item(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, item, track)): Path<(u64, u64, u64)>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
+
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
+    let playlist_track = playlist_item::find(&pool, track as i64)
+        .await?
+        .filter(|playlist_track| playlist_track.playlist == playlist.id)
+        .or_not_found()?;
+
+    playlist_item::delete(&pool, track as i64).await?;
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::PlaylistItemRemove(playlist, playlist_track),
+    )
+    .await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[get("/{id}/playlists/{item}/export")]
+pub async fn get_guild_playlist_export(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, item)): Path<(u64, u64)>,
+    Query(mut query): Query<HashMap<String, String>>,
+) -> ApiResult<HttpResponse> {
+    user.has_read_guild(&redis_pool, id).await?;
+
+    let format = query
+        .remove("format")
+        .map(|format| format.parse::<PlaylistFormat>().or_bad_request())
+        .transpose()?
+        .unwrap_or(PlaylistFormat::Json);
+
+    let playlist = playlist::find(&pool, item as i64)
+        .await?
+        .filter(|playlist| playlist.guild == Some(id as i64))
+        .or_not_found()?;
+    let tracks = utils::playlist::get_items(&pool, &playlist).await?;
+
+    let content = utils::playlist::export(format, playlist.name.as_str(), tracks)?;
+    let file_name = playlist
+        .name
+        .chars()
+        .filter(|char| char.is_ascii_alphanumeric() || *char == ' ' || *char == '-')
+        .collect::<String>();
+    let file_name = match file_name.trim() {
+        "" => "playlist",
+        file_name => file_name,
+    };
+
+    Ok(HttpResponse::Ok()
+        .header(CONTENT_TYPE, format.content_type())
+        .header(
+            CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"{}.{}\"",
+                file_name,
+                format.extension()
+            ),
+        )
+        .body(content))
+}
+
+#[post("/{id}/playlists/import")]
+pub async fn post_guild_playlists_import(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+    Json(import): Json<SimplePlaylistImport>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManagePlaylist)
+        .await?;
+
+    let playlists = playlist::find_by_guild(&pool, id as i64).await?;
+    let content = utils::playlist::parse(import.format, import.content.as_str())?;
+    let new_playlist = NewPlaylist {
+        guild: Some(id as i64),
+        author: user.user.id,
+        name: import.name.or(content.name).unwrap_or_default(),
+    };
+
+    new_playlist.check()?;
+
+    if playlists.len() >= PLAYLIST_MAX {
+        return ApiResponse::bad_request()
+            .message("error.playlist_limit")
+            .finish();
+    }
+
+    if playlists
+        .iter()
+        .any(|playlist| playlist.name.to_lowercase() == new_playlist.name.to_lowercase())
+    {
+        return ApiResponse::bad_request()
+            .message("error.playlist_exists")
+            .finish();
+    }
+
+    if content.items.len() > PLAYLIST_IMPORT_MAX {
+        return ApiResponse::bad_request()
+            .message("error.playlist_too_long")
+            .finish();
+    }
+
+    let resolved: Vec<_> = stream::iter(content.items)
+        .map(|item| async move {
+            let track = utils::playlist::resolve(&item).await;
+            (item, track)
+        })
+        .buffered(PLAYLIST_IMPORT_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut tracks = vec![];
+    let mut failed = vec![];
+    for (item, track) in resolved {
+        match track {
+            Some(track) => tracks.push(track),
+            None => failed.push(item),
+        }
+    }
+
+    if tracks.is_empty() {
+        return ApiResponse::bad_request()
+            .message("error.playlist_tracks_not_found")
+            .finish();
+    }
+
+    let items: Vec<NewPlaylistItem> = tracks
+        .into_iter()
+        .enumerate()
+        .map(|(index, track)| NewPlaylistItem {
+            playlist: 0,
+            track: track.track,
+            title: track.info.title,
+            uri: track.info.uri,
+            length: track.info.length as i32,
+            position: index as i32,
+        })
+        .collect();
+
+    let imported = items.len();
+    let playlist = playlist::create(&pool, new_playlist.clone(), items).await?;
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::PlaylistAdd(new_playlist),
+    )
+    .await?;
+
+    let import = PlaylistImport {
+        id: playlist.id,
+        imported,
+        failed,
+    };
+
+    ApiResponse::ok().data(import).finish()
+}
@@ -1,4 +1,6 @@
 use crate::db::{cache, PgPool, RedisPool};
+use crate::models::favourite;
+use crate::models::guild_permission::Permission;
 use crate::routes::{ApiResponse, ApiResult, OptionExt};
 use crate::utils::auth::User;
 use crate::utils::log::{self, LogInfo};
@@ -10,6 +12,7 @@ use actix_web::web::{Data, Json, Path};
 use actix_web::{delete, get, post, put};
 use percent_encoding::{percent_encode, NON_ALPHANUMERIC};
 use serde::Deserialize;
+use std::collections::HashSet;
 use twilight_andesite::model::Stop;
 use twilight_model::id::GuildId;
 
@@ -45,7 +48,8 @@ pub async fn post_guild_queue(
     Path(id): Path<u64>,
     Json(item): Json<SimpleQueueItem>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_track(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::AddTrack)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
     let config = cache::get_config(&pool, &redis_pool, id).await?;
@@ -53,7 +57,7 @@ pub async fn post_guild_queue(
 
     if tracks.len() >= config.max_queue as usize {
         return ApiResponse::bad_request()
-            .message("The queue is already at maximum length.")
+            .message("error.queue_full")
             .finish();
     }
 
@@ -64,9 +68,7 @@ pub async fn post_guild_queue(
                 .as_str(),
         )
         .await
-        .map_err(|_| {
-            ApiResponse::bad_request().message("The requested track could not be found.")
-        })?;
+        .map_err(|_| ApiResponse::bad_request().message("error.track_not_found"))?;
 
         QueueItem {
             track: decoded_track.track,
@@ -81,7 +83,7 @@ pub async fn post_guild_queue(
 
     if config.no_duplicate && tracks.iter().any(|item| item.track == track.track) {
         return ApiResponse::bad_request()
-            .message("Duplicated tracks are not allowed in this server.")
+            .message("error.duplicate_track")
             .finish();
     }
 
@@ -100,6 +102,76 @@ pub async fn post_guild_queue(
     ApiResponse::ok().finish()
 }
 
+#[post("/{id}/queue/favourites")]
+pub async fn post_guild_queue_favourites(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::AddTrack)
+        .await?;
+    user.is_connected(&redis_pool, id, true).await?;
+
+    let config = cache::get_config(&pool, &redis_pool, id).await?;
+    let mut tracks = queue::get(&redis_pool, id).await?;
+    let amount = tracks.len();
+
+    let mut favourites: Vec<QueueItem> = favourite::find_by_author(&pool, user.user.id)
+        .await?
+        .into_iter()
+        .map(|favourite| QueueItem {
+            track: favourite.track,
+            title: favourite.title,
+            uri: favourite.uri,
+            length: favourite.length,
+            author: user.user.id,
+            username: user.user.username.clone(),
+            discriminator: user.user.discriminator,
+        })
+        .collect();
+
+    if favourites.is_empty() {
+        return ApiResponse::bad_request()
+            .message("error.favourite_empty")
+            .finish();
+    }
+
+    if config.no_duplicate {
+        let mut existing: HashSet<String> = tracks.iter().map(|item| item.track.clone()).collect();
+        favourites.retain(|favourite| existing.insert(favourite.track.clone()));
+    }
+
+    if amount + favourites.len() > config.max_queue as usize {
+        return ApiResponse::bad_request()
+            .message("error.queue_full")
+            .finish();
+    }
+
+    let added = favourites.len();
+    tracks.extend(favourites);
+    queue::set(&redis_pool, id, tracks).await?;
+
+    let player = get_player(&redis_pool, id).await?;
+    if player.position.is_none() && added > 0 {
+        queue::set_playing(&redis_pool, id, amount as i32).await?;
+        queue::play(&redis_pool, id).await?;
+    }
+
+    log::register(
+        &pool,
+        &redis_pool,
+        id,
+        user,
+        LogInfo::QueueAddFavourites(added as u64),
+    )
+    .await?;
+
+    polling::notify(id)?;
+
+    ApiResponse::ok().finish()
+}
+
 #[delete("/{id}/queue")]
 pub async fn delete_guild_queue(
     user: User,
@@ -107,7 +179,8 @@ pub async fn delete_guild_queue(
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_queue(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::ClearQueue)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
     if get_player(&redis_pool, id).await.is_ok() {
@@ -130,7 +203,8 @@ pub async fn post_guild_queue_shuffle(
     redis_pool: Data<RedisPool>,
     Path(id): Path<u64>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_queue(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::Shuffle)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
     queue::shuffle(&redis_pool, id).await?;
@@ -150,7 +224,8 @@ pub async fn put_guild_queue_item_position(
     Path((id, item)): Path<(u64, u32)>,
     Json(new_position): Json<SimplePosition>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_queue(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::MoveTrack)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
     let track = queue::get_track(&redis_pool, id, item as i32)
@@ -160,13 +235,13 @@ pub async fn put_guild_queue_item_position(
 
     if playing == item as i32 || playing == new_position.position as i32 {
         return ApiResponse::bad_request()
-            .message("The position of the currently playing track cannot be changed.")
+            .message("error.playing_shift")
             .finish();
     }
 
     if queue::len(&redis_pool, id).await? <= new_position.position as usize {
         return ApiResponse::bad_request()
-            .message("The position to move the track to is invalid.")
+            .message("error.position_invalid")
             .finish();
     }
 
@@ -193,7 +268,8 @@ pub async fn delete_guild_queue_item(
     redis_pool: Data<RedisPool>,
     Path((id, item)): Path<(u64, u32)>,
 ) -> ApiResult<ApiResponse> {
-    user.has_manage_queue(&pool, &redis_pool, id).await?;
+    user.has_permission(&pool, &redis_pool, id, Permission::RemoveTrack)
+        .await?;
     user.is_connected(&redis_pool, id, true).await?;
 
     queue::get_track(&redis_pool, id, item as i32)
@@ -202,7 +278,7 @@ pub async fn delete_guild_queue_item(
 
     if queue::get_playing(&redis_pool, id).await? == item as i32 {
         return ApiResponse::bad_request()
-            .message("The currently playing track cannot be removed.")
+            .message("error.playing_remove")
             .finish();
     }
 
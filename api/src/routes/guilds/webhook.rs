@@ -0,0 +1,73 @@
+use crate::db::{PgPool, RedisPool};
+use crate::models::guild_permission::Permission;
+use crate::models::guild_webhook::{self, NewGuildWebhook};
+use crate::models::Validate;
+use crate::routes::{ApiResponse, ApiResult, OptionExt};
+use crate::utils::auth::User;
+
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, put};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SimpleWebhook {
+    pub url: String,
+}
+
+#[get("/{id}/webhooks")]
+pub async fn get_guild_webhooks(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(id): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    let webhooks = guild_webhook::find_by_guild(&pool, id as i64).await?;
+
+    ApiResponse::ok().data(webhooks).finish()
+}
+
+#[put("/{id}/webhooks/{category}")]
+pub async fn put_guild_webhook(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, category)): Path<(u64, String)>,
+    Json(webhook): Json<SimpleWebhook>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    let new_webhook = NewGuildWebhook {
+        guild: id as i64,
+        category,
+        url: webhook.url.trim().to_owned(),
+    };
+
+    new_webhook.check()?;
+
+    let webhook = guild_webhook::create(&pool, new_webhook).await?;
+
+    ApiResponse::ok().data(webhook).finish()
+}
+
+#[delete("/{id}/webhooks/{category}")]
+pub async fn delete_guild_webhook(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path((id, category)): Path<(u64, String)>,
+) -> ApiResult<ApiResponse> {
+    user.has_permission(&pool, &redis_pool, id, Permission::ManageGuild)
+        .await?;
+
+    guild_webhook::find(&pool, id as i64, category.clone())
+        .await?
+        .or_not_found()?;
+
+    guild_webhook::delete(&pool, id as i64, category).await?;
+
+    ApiResponse::ok().finish()
+}
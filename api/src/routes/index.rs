@@ -1,13 +1,14 @@
 use crate::constants::{PLAYER_STATS_KEY, STATS_KEY, STATUS_KEY};
 use crate::db::cache::models::{Stats, Status};
-use crate::db::{cache, RedisPool};
+use crate::db::{cache, PgPool, RedisPool};
 use crate::routes::{ApiResponse, ApiResult, OptionExt, ResultExt};
 use crate::utils::auth::{
     get_csrf_redirect, get_invite_uri, get_redirect_uri, get_token_cookie, token_exchange,
 };
 
 use actix_web::web::{Data, Json, Query};
-use actix_web::{get, post};
+use actix_web::{get, post, HttpRequest};
+use http::header::USER_AGENT;
 use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -66,6 +67,8 @@ pub async fn get_invite(Query(query): Query<HashMap<String, String>>) -> ApiResu
 
 #[post("/authorize")]
 pub async fn get_authorize(
+    req: HttpRequest,
+    pool: Data<PgPool>,
     redis_pool: Data<RedisPool>,
     Json(auth): Json<SimpleAuth>,
 ) -> ApiResult<ApiResponse> {
@@ -77,8 +80,14 @@ pub async fn get_authorize(
         None
     };
 
+    let device = req
+        .headers()
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
     let redirect = SimpleRedirect { uri };
-    let cookie = get_token_cookie(token).await?;
+    let cookie = get_token_cookie(&pool, &redis_pool, token, device).await?;
 
     ApiResponse::ok().data(redirect).set_cookie(cookie).finish()
 }
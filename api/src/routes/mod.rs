@@ -1,3 +1,5 @@
+use crate::utils::locale::{get_language, Translation, DEFAULT_LANGUAGE};
+
 use actix_web::client::HttpError;
 use actix_web::cookie::Cookie;
 use actix_web::error::BlockingError;
@@ -11,6 +13,7 @@ use redis::RedisError;
 use serde::export::Formatter;
 use serde::Serialize;
 use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fmt::{self, Debug, Display};
 use std::io;
 use std::net::AddrParseError;
@@ -34,6 +37,7 @@ pub type ApiResult<T> = Result<T, ApiError>;
 pub struct ApiResponse {
     pub status: StatusCode,
     pub data: Value,
+    pub message: Option<Translation>,
     pub error: Option<ApiError>,
     pub set_cookie: Option<Cookie<'static>>,
     pub del_cookie: Option<String>,
@@ -55,14 +59,19 @@ impl ApiResponse {
             Ok(value) => self.data = value,
             Err(err) => self.error = Some(err.into()),
         }
+        self.message = None;
         self
     }
 
-    pub fn message(mut self, message: &str) -> Self {
-        self.data = json!({ "message": message });
+    pub fn message(mut self, message: impl Into<Translation>) -> Self {
+        self.message = Some(message.into());
         self
     }
 
+    pub fn body(&self, language: &str) -> Value {
+        get_body(&self.data, &self.message, language)
+    }
+
     pub fn set_cookie(mut self, set_cookie: Cookie<'static>) -> Self {
         self.set_cookie = Some(set_cookie);
         self
@@ -76,7 +85,8 @@ impl ApiResponse {
     pub fn ok() -> Self {
         Self {
             status: StatusCode::OK,
-            data: json!({"message": "The request made is successful."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.ok")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -86,7 +96,8 @@ impl ApiResponse {
     pub fn bad_request() -> Self {
         Self {
             status: StatusCode::BAD_REQUEST,
-            data: json!({"message": "The request you made is invalid."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.bad_request")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -96,7 +107,8 @@ impl ApiResponse {
     pub fn unauthorized() -> Self {
         Self {
             status: StatusCode::UNAUTHORIZED,
-            data: json!({"message": "You are not authorised to access this resource."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.unauthorized")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -106,7 +118,8 @@ impl ApiResponse {
     pub fn forbidden() -> Self {
         Self {
             status: StatusCode::FORBIDDEN,
-            data: json!({"message": "You do not have permission to perform this action."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.forbidden")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -116,7 +129,8 @@ impl ApiResponse {
     pub fn not_found() -> Self {
         Self {
             status: StatusCode::NOT_FOUND,
-            data: json!({"message": "The requested resource could not be found."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.not_found")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -126,7 +140,19 @@ impl ApiResponse {
     pub fn request_timeout() -> Self {
         Self {
             status: StatusCode::REQUEST_TIMEOUT,
-            data: json!({"message": "The server did not receive a complete request."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.request_timeout")),
+            error: None,
+            set_cookie: None,
+            del_cookie: None,
+        }
+    }
+
+    pub fn payload_too_large() -> Self {
+        Self {
+            status: StatusCode::PAYLOAD_TOO_LARGE,
+            data: Value::Null,
+            message: Some(Translation::new("error.payload_too_large")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -136,7 +162,8 @@ impl ApiResponse {
     pub fn internal_server_error() -> Self {
         Self {
             status: StatusCode::INTERNAL_SERVER_ERROR,
-            data: json!({"message": "The server encountered an internal error."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.internal_server_error")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -146,7 +173,8 @@ impl ApiResponse {
     pub fn service_unavailable() -> Self {
         Self {
             status: StatusCode::SERVICE_UNAVAILABLE,
-            data: json!({"message": "The server cannot handle your request at this time."}),
+            data: Value::Null,
+            message: Some(Translation::new("error.service_unavailable")),
             error: None,
             set_cookie: None,
             del_cookie: None,
@@ -159,6 +187,7 @@ impl Responder for ApiResponse {
     type Future = Ready<Result<HttpResponse, actix_web::Error>>;
 
     fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        let body = self.body(get_language(req).as_str());
         let mut res = HttpResponse::build(self.status);
 
         if let Some(set_cookie) = self.set_cookie {
@@ -171,7 +200,14 @@ impl Responder for ApiResponse {
             }
         }
 
-        ok(res.json(self.data))
+        ok(res.json(body))
+    }
+}
+
+fn get_body(data: &Value, message: &Option<Translation>, language: &str) -> Value {
+    match message {
+        Some(message) => json!({ "message": message.render(language) }),
+        None => data.clone(),
     }
 }
 
@@ -179,7 +215,7 @@ impl Responder for ApiResponse {
 pub enum ApiError {
     ActixWebError(()),
     AddrParseError(AddrParseError),
-    CustomError((StatusCode, Value)),
+    CustomError((StatusCode, Value, Option<Translation>)),
     DieselR2d2Error(diesel::r2d2::Error),
     DieselResultError(diesel::result::Error),
     EmptyError(()),
@@ -210,18 +246,26 @@ impl Display for ApiError {
     }
 }
 
-impl ResponseError for ApiError {
-    fn error_response(&self) -> HttpResponse {
+impl ApiError {
+    pub fn body(&self, language: &str) -> Value {
         match self {
-            Self::CustomError((status, value)) => HttpResponse::build(*status).json(value),
-            _ => {
-                let res = ApiResponse::internal_server_error();
-                HttpResponse::build(res.status).json(&res.data)
-            },
+            Self::CustomError((_, value, message)) => get_body(value, message, language),
+            _ => ApiResponse::internal_server_error().body(language),
         }
     }
 }
 
+impl ResponseError for ApiError {
+    fn error_response(&self) -> HttpResponse {
+        let status = match self {
+            Self::CustomError((status, _, _)) => *status,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        HttpResponse::build(status).json(self.body(DEFAULT_LANGUAGE))
+    }
+}
+
 impl From<actix_web::Error> for ApiError {
     fn from(err: actix_web::Error) -> Self {
         sentry::capture_error(&err);
@@ -268,7 +312,7 @@ impl From<BlockingError<RedisError>> for ApiError {
 
 impl From<ApiResponse> for ApiError {
     fn from(err: ApiResponse) -> Self {
-        Self::CustomError((err.status, err.data))
+        Self::CustomError((err.status, err.data, err.message))
     }
 }
 
@@ -433,8 +477,32 @@ impl From<Utf8Error> for ApiError {
     }
 }
 
+pub fn get_page(
+    query: &mut HashMap<String, String>,
+    max: usize,
+    message: &str,
+) -> ApiResult<(i64, i64)> {
+    let offset = query
+        .remove("offset")
+        .map(|offset| offset.parse::<u32>().or_bad_request())
+        .transpose()?
+        .unwrap_or_default();
+    let limit = query
+        .remove("limit")
+        .map(|limit| limit.parse::<u32>().or_bad_request())
+        .transpose()?
+        .unwrap_or(max as u32);
+
+    if limit == 0 || limit as usize > max {
+        return Err(ApiResponse::bad_request().message(message).into());
+    }
+
+    Ok((offset as i64, limit as i64))
+}
+
 pub trait OptionExt<T> {
     fn or_bad_request(self) -> ApiResult<T>;
+    fn or_unauthorized(self) -> ApiResult<T>;
     fn or_not_found(self) -> ApiResult<T>;
     fn or_internal_error(self) -> ApiResult<T>;
 }
@@ -444,6 +512,10 @@ impl<T> OptionExt<T> for Option<T> {
         self.ok_or_else(|| ApiResponse::bad_request().into())
     }
 
+    fn or_unauthorized(self) -> ApiResult<T> {
+        self.ok_or_else(|| ApiResponse::unauthorized().into())
+    }
+
     fn or_not_found(self) -> ApiResult<T> {
         self.ok_or_else(|| ApiResponse::not_found().into())
     }
@@ -455,6 +527,7 @@ impl<T> OptionExt<T> for Option<T> {
 
 pub trait ResultExt<T, E> {
     fn or_bad_request(self) -> ApiResult<T>;
+    fn or_unauthorized(self) -> ApiResult<T>;
     fn or_not_found(self) -> ApiResult<T>;
     fn or_internal_error(self) -> ApiResult<T>;
 }
@@ -464,6 +537,10 @@ impl<T, E> ResultExt<T, E> for Result<T, E> {
         self.map_err(|_| ApiResponse::bad_request().into())
     }
 
+    fn or_unauthorized(self) -> ApiResult<T> {
+        self.map_err(|_| ApiResponse::unauthorized().into())
+    }
+
     fn or_not_found(self) -> ApiResult<T> {
         self.map_err(|_| ApiResponse::not_found().into())
     }
@@ -1,5 +1,6 @@
 use crate::routes::{ApiResponse, ApiResult, OptionExt, ResultExt};
 use crate::utils::auth::User;
+use crate::utils::locale::Translation;
 use crate::utils::{html_unescape, player};
 
 use actix_web::get;
@@ -55,7 +56,7 @@ pub async fn get_tracks(
         if let Some(cause) = tracks.cause {
             if let Some(message) = cause.message {
                 return ApiResponse::bad_request()
-                    .message(message.as_str())
+                    .message(Translation::new("error.track_load_failed").arg("error", message))
                     .finish();
             }
         }
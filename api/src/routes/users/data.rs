@@ -0,0 +1,84 @@
+use crate::constants::COOKIE_NAME;
+use crate::db::{cache, PgPool, RedisPool};
+use crate::models::account::Account;
+use crate::models::favourite::{self, Favourite};
+use crate::models::history::{self, History};
+use crate::models::playlist::{self, Playlist};
+use crate::models::playlist_item::{self, PlaylistItem};
+use crate::models::user_config::{self, UserConfig};
+use crate::models::user_session::{self, UserSessionSummary};
+use crate::models::user_token::{self, UserToken};
+use crate::routes::{ApiResponse, ApiResult};
+use crate::utils::auth::User;
+
+use actix_web::web::Data;
+use actix_web::{delete, get};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct UserPlaylist {
+    #[serde(flatten)]
+    pub playlist: Playlist,
+    pub items: Vec<PlaylistItem>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserData {
+    pub user: Account,
+    pub playlists: Vec<UserPlaylist>,
+    pub favourites: Vec<Favourite>,
+    pub history: Vec<History>,
+    pub settings: Option<UserConfig>,
+    pub tokens: Vec<UserToken>,
+    pub sessions: Vec<UserSessionSummary>,
+}
+
+#[get("/@me/data")]
+pub async fn get_user_me_data(user: User, pool: Data<PgPool>) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    let mut playlists = vec![];
+    for playlist in playlist::find_by_author(&pool, user.user.id).await? {
+        let items = playlist_item::find_by_playlist(&pool, playlist.id).await?;
+        playlists.push(UserPlaylist { playlist, items });
+    }
+
+    let data = UserData {
+        playlists,
+        favourites: favourite::find_by_author(&pool, user.user.id).await?,
+        history: history::find_by_account(&pool, user.user.id).await?,
+        settings: user_config::find(&pool, user.user.id).await?,
+        tokens: user_token::find_by_author(&pool, user.user.id).await?,
+        sessions: user_session::find_by_author(&pool, user.user.id)
+            .await?
+            .into_iter()
+            .map(|session| session.into())
+            .collect(),
+        user: user.user,
+    };
+
+    ApiResponse::ok().data(data).finish()
+}
+
+#[delete("/@me/data")]
+pub async fn delete_user_me_data(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    playlist::delete_by_author(&pool, user.user.id).await?;
+    favourite::delete_by_author(&pool, user.user.id).await?;
+    history::delete_by_account(&pool, user.user.id).await?;
+    user_config::delete(&pool, user.user.id).await?;
+    user_token::delete_by_author(&pool, user.user.id).await?;
+    cache::invalidate_user_config(&redis_pool, user.user.id as u64).await?;
+
+    for session in user_session::find_by_author(&pool, user.user.id).await? {
+        cache::invalidate_user_session(&redis_pool, session.id as u64).await?;
+    }
+    user_session::delete_by_author(&pool, user.user.id).await?;
+
+    ApiResponse::ok().del_cookie(COOKIE_NAME).finish()
+}
@@ -0,0 +1,35 @@
+use crate::db::PgPool;
+use crate::models::favourite;
+use crate::models::user_token::TokenScope;
+use crate::routes::{ApiResponse, ApiResult, OptionExt};
+use crate::utils::auth::User;
+
+use actix_web::web::{Data, Path};
+use actix_web::{delete, get};
+
+#[get("/@me/favourites")]
+pub async fn get_user_me_favourites(user: User, pool: Data<PgPool>) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Read, None)?;
+
+    let favourites = favourite::find_by_author(&pool, user.user.id).await?;
+
+    ApiResponse::ok().data(favourites).finish()
+}
+
+#[delete("/@me/favourites/{item}")]
+pub async fn delete_user_me_favourite(
+    user: User,
+    pool: Data<PgPool>,
+    Path(item): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    favourite::find(&pool, item as i64)
+        .await?
+        .filter(|favourite| favourite.author == user.user.id)
+        .or_not_found()?;
+
+    favourite::delete(&pool, item as i64).await?;
+
+    ApiResponse::ok().finish()
+}
@@ -0,0 +1,46 @@
+use crate::constants::FETCH_HISTORY_MAX;
+use crate::db::{PgPool, RedisPool};
+use crate::models::history;
+use crate::routes::{get_page, ApiResponse, ApiResult, ResultExt};
+use crate::utils::auth::User;
+
+use actix_web::get;
+use actix_web::web::{Data, Query};
+use std::collections::HashMap;
+
+#[get("/@me/history")]
+pub async fn get_user_me_history(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Query(mut query): Query<HashMap<String, String>>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    let (offset, limit) = get_page(&mut query, FETCH_HISTORY_MAX, "error.history_fetch_invalid")?;
+    let guild = query
+        .remove("guild")
+        .map(|guild| guild.parse::<u64>().or_bad_request())
+        .transpose()?;
+
+    let guilds = if let Some(guild) = guild {
+        user.has_read_guild(&redis_pool, guild).await?;
+
+        vec![guild as i64]
+    } else {
+        let mut guilds = vec![];
+
+        for guild in history::find_guilds_by_account(&pool, user.user.id).await? {
+            if user.has_read_guild(&redis_pool, guild as u64).await.is_ok() {
+                guilds.push(guild);
+            }
+        }
+
+        guilds
+    };
+
+    let histories =
+        history::find_page_by_account(&pool, user.user.id, guilds, offset, limit).await?;
+
+    ApiResponse::ok().data(histories).finish()
+}
@@ -2,14 +2,31 @@ use crate::constants::{user_key, COOKIE_NAME, FETCH_USERS_MAX, USER_KEY_TTL};
 use crate::db::pubsub::Message;
 use crate::db::{cache, PgPool, RedisPool};
 use crate::models::account::{self, Account};
+use crate::models::guild_permission::Permission;
 use crate::routes::{ApiResponse, ApiResult, OptionExt, ResultExt};
 use crate::utils::auth::User;
 
 use actix_web::web::{Data, Path, Query};
 use actix_web::{get, post};
-use serde_json::json;
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
+pub mod data;
+pub mod favourite;
+pub mod history;
+pub mod playlist;
+pub mod session;
+pub mod settings;
+pub mod token;
+
+pub use data::*;
+pub use favourite::*;
+pub use history::*;
+pub use playlist::*;
+pub use session::*;
+pub use settings::*;
+pub use token::*;
+
 #[get("")]
 pub async fn get_users(
     _user: User,
@@ -25,7 +42,7 @@ pub async fn get_users(
 
     if ids.len() > FETCH_USERS_MAX {
         return ApiResponse::bad_request()
-            .message("The request has exceeded the limit for the maximum number of users.")
+            .message("error.user_fetch_limit")
             .finish();
     }
 
@@ -62,14 +79,14 @@ pub async fn get_user(
 
 #[get("/@me")]
 pub async fn get_user_me(user: User) -> ApiResult<ApiResponse> {
-    user.is_not_bot()?;
+    user.is_session()?;
 
     ApiResponse::ok().data(user.user).finish()
 }
 
 #[get("/@me/guilds")]
 pub async fn get_user_me_guilds(user: User, redis_pool: Data<RedisPool>) -> ApiResult<ApiResponse> {
-    user.is_not_bot()?;
+    user.is_session()?;
 
     let guilds = user.get_guilds(&redis_pool).await?;
 
@@ -85,22 +102,27 @@ pub async fn get_users_me_guild(
 ) -> ApiResult<ApiResponse> {
     user.has_read_guild(&redis_pool, id).await?;
 
-    let permissions = json!({
-        "manage_guild": user.has_manage_guild(&pool, &redis_pool, id).await.is_ok(),
-        "manage_playlist": user.has_manage_playlist(&pool, &redis_pool, id).await.is_ok(),
-        "manage_player": user.has_manage_player(&pool, &redis_pool, id).await.is_ok(),
-        "manage_queue": user.has_manage_queue(&pool, &redis_pool, id).await.is_ok(),
-        "manage_track": user.has_manage_track(&pool, &redis_pool, id).await.is_ok(),
-    });
+    let permissions = user.get_permissions(&pool, &redis_pool, id).await?;
+    let permissions: Map<String, Value> = Permission::ALL
+        .iter()
+        .map(|permission| {
+            let value = Value::Bool(permissions.contains(permission));
+            (permission.as_str().to_owned(), value)
+        })
+        .collect();
 
     ApiResponse::ok().data(permissions).finish()
 }
 
 #[post("/@me/logout")]
-pub async fn post_user_me_logout(user: User) -> ApiResult<ApiResponse> {
-    user.is_not_bot()?;
+pub async fn post_user_me_logout(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
 
-    user.revoke_token().await?;
+    user.revoke_session(&pool, &redis_pool).await?;
 
     ApiResponse::ok().del_cookie(COOKIE_NAME).finish()
 }
@@ -0,0 +1,255 @@
+use crate::constants::{FETCH_PLAYLIST_ITEMS_MAX, USER_PLAYLIST_ITEM_MAX, USER_PLAYLIST_MAX};
+use crate::db::{PgPool, RedisPool};
+use crate::models::playlist::{self, EditPlaylist, NewPlaylist, Playlist};
+use crate::models::playlist_item::{self, NewPlaylistItem};
+use crate::models::user_token::TokenScope;
+use crate::models::{string_int_opt, Validate};
+use crate::routes::guilds::{SimplePlaylistItems, SimplePosition};
+use crate::routes::{get_page, ApiResponse, ApiResult, OptionExt};
+use crate::utils;
+use crate::utils::auth::User;
+use crate::utils::queue;
+
+use actix_web::web::{Data, Json, Path, Query};
+use actix_web::{delete, get, patch, post, put};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize)]
+pub struct SimpleUserPlaylist {
+    pub name: String,
+    #[serde(default, deserialize_with = "string_int_opt")]
+    pub guild: Option<u64>,
+}
+
+async fn find_personal(pool: &PgPool, user: &User, id: u64) -> ApiResult<Playlist> {
+    let playlist = playlist::find(pool, id as i64)
+        .await?
+        .filter(|playlist| playlist.is_personal() && playlist.author == user.user.id)
+        .or_not_found()?;
+
+    Ok(playlist)
+}
+
+#[get("/@me/playlists")]
+pub async fn get_user_me_playlists(user: User, pool: Data<PgPool>) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Read, None)?;
+
+    let playlists = playlist::find_summary_by_author(&pool, user.user.id).await?;
+
+    ApiResponse::ok().data(playlists).finish()
+}
+
+#[get("/@me/playlists/{item}/items")]
+pub async fn get_user_me_playlist_items(
+    user: User,
+    pool: Data<PgPool>,
+    Path(item): Path<u64>,
+    Query(mut query): Query<HashMap<String, String>>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Read, None)?;
+
+    let (offset, limit) = get_page(
+        &mut query,
+        FETCH_PLAYLIST_ITEMS_MAX,
+        "error.track_fetch_invalid",
+    )?;
+
+    find_personal(&pool, &user, item).await?;
+
+    let items = playlist_item::find_page_by_playlist(&pool, item as i64, offset, limit).await?;
+
+    ApiResponse::ok().data(items).finish()
+}
+
+#[post("/@me/playlists")]
+pub async fn post_user_me_playlists(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Json(new_playlist): Json<SimpleUserPlaylist>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    let playlists = playlist::find_by_author(&pool, user.user.id).await?;
+    let tracks = if let Some(guild) = new_playlist.guild {
+        user.has_read_guild(&redis_pool, guild).await?;
+        queue::get(&redis_pool, guild).await?
+    } else {
+        vec![]
+    };
+    let new_playlist = NewPlaylist {
+        guild: None,
+        author: user.user.id,
+        name: new_playlist.name,
+    };
+
+    new_playlist.check()?;
+
+    if playlists.len() >= USER_PLAYLIST_MAX {
+        return ApiResponse::bad_request()
+            .message("error.user_playlist_limit")
+            .finish();
+    }
+
+    if playlists
+        .iter()
+        .any(|playlist| playlist.name.to_lowercase() == new_playlist.name.to_lowercase())
+    {
+        return ApiResponse::bad_request()
+            .message("error.playlist_exists")
+            .finish();
+    }
+
+    if tracks.len() > USER_PLAYLIST_ITEM_MAX {
+        return ApiResponse::bad_request()
+            .message("error.queue_too_long")
+            .finish();
+    }
+
+    let items = tracks
+        .into_iter()
+        .enumerate()
+        .map(|(index, track)| NewPlaylistItem {
+            playlist: 0,
+            track: track.track,
+            title: track.title,
+            uri: track.uri,
+            length: track.length,
+            position: index as i32,
+        })
+        .collect();
+
+    let playlist = playlist::create(&pool, new_playlist, items).await?;
+
+    ApiResponse::ok().data(playlist).finish()
+}
+
+#[patch("/@me/playlists/{item}")]
+pub async fn patch_user_me_playlist(
+    user: User,
+    pool: Data<PgPool>,
+    Path(item): Path<u64>,
+    Json(new_playlist): Json<EditPlaylist>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    find_personal(&pool, &user, item).await?;
+    new_playlist.check()?;
+
+    if let Some(name) = &new_playlist.name {
+        if playlist::find_by_author(&pool, user.user.id)
+            .await?
+            .iter()
+            .any(|playlist| {
+                playlist.id != item as i64 && playlist.name.to_lowercase() == name.to_lowercase()
+            })
+        {
+            return ApiResponse::bad_request()
+                .message("error.playlist_exists")
+                .finish();
+        }
+    }
+
+    playlist::update(&pool, item as i64, new_playlist).await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[delete("/@me/playlists/{item}")]
+pub async fn delete_user_me_playlist(
+    user: User,
+    pool: Data<PgPool>,
+    Path(item): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    find_personal(&pool, &user, item).await?;
+
+    playlist::delete(&pool, item as i64).await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[post("/@me/playlists/{item}/items")]
+pub async fn post_user_me_playlist_items(
+    user: User,
+    pool: Data<PgPool>,
+    Path(item): Path<u64>,
+    Json(new_items): Json<SimplePlaylistItems>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    let playlist = find_personal(&pool, &user, item).await?;
+
+    if new_items.tracks.is_empty() {
+        return ApiResponse::bad_request()
+            .message("error.playlist_add_empty")
+            .finish();
+    }
+
+    let items = utils::playlist::decode_tracks(new_items.tracks.as_slice())
+        .await?
+        .into_iter()
+        .enumerate()
+        .map(|(index, track)| NewPlaylistItem {
+            playlist: playlist.id,
+            track: track.track,
+            title: track.info.title,
+            uri: track.info.uri,
+            length: track.info.length as i32,
+            position: index as i32,
+        })
+        .collect();
+
+    playlist_item::batch_create(&pool, playlist.id, items, USER_PLAYLIST_ITEM_MAX).await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[put("/@me/playlists/{item}/items/{track}/position")]
+pub async fn put_user_me_playlist_item_position(
+    user: User,
+    pool: Data<PgPool>,
+    Path((item, track)): Path<(u64, u64)>,
+    Json(new_position): Json<SimplePosition>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    let playlist = find_personal(&pool, &user, item).await?;
+
+    playlist_item::find(&pool, track as i64)
+        .await?
+        .filter(|playlist_track| playlist_track.playlist == playlist.id)
+        .or_not_found()?;
+
+    if playlist_item::count_by_playlist(&pool, item as i64).await? <= new_position.position as i64 {
+        return ApiResponse::bad_request()
+            .message("error.position_invalid")
+            .finish();
+    }
+
+    playlist_item::shift(&pool, track as i64, new_position.position as i32).await?;
+
+    ApiResponse::ok().finish()
+}
+
+#[delete("/@me/playlists/{item}/items/{track}")]
+pub async fn delete_user_me_playlist_item(
+    user: User,
+    pool: Data<PgPool>,
+    Path((item, track)): Path<(u64, u64)>,
+) -> ApiResult<ApiResponse> {
+    user.has_scope(TokenScope::Playlist, None)?;
+
+    let playlist = find_personal(&pool, &user, item).await?;
+
+    playlist_item::find(&pool, track as i64)
+        .await?
+        .filter(|playlist_track| playlist_track.playlist == playlist.id)
+        .or_not_found()?;
+
+    playlist_item::delete(&pool, track as i64).await?;
+
+    ApiResponse::ok().finish()
+}
@@ -0,0 +1,58 @@
+use crate::constants::COOKIE_NAME;
+use crate::db::{cache, PgPool, RedisPool};
+use crate::models::user_session::{self, UserSessionSummary};
+use crate::routes::{ApiResponse, ApiResult, OptionExt};
+use crate::utils::auth::{revoke_token, User};
+
+use actix_web::web::{Data, Path};
+use actix_web::{delete, get};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct SimpleSession {
+    #[serde(flatten)]
+    pub session: UserSessionSummary,
+    pub current: bool,
+}
+
+#[get("/@me/sessions")]
+pub async fn get_user_me_sessions(user: User, pool: Data<PgPool>) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    let sessions: Vec<SimpleSession> = user_session::find_by_author(&pool, user.user.id)
+        .await?
+        .into_iter()
+        .filter(|session| !session.is_expired())
+        .map(|session| SimpleSession {
+            current: Some(session.id) == user.session,
+            session: session.into(),
+        })
+        .collect();
+
+    ApiResponse::ok().data(sessions).finish()
+}
+
+#[delete("/@me/sessions/{item}")]
+pub async fn delete_user_me_session(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Path(item): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    let session = user_session::find(&pool, item as i64)
+        .await?
+        .filter(|session| session.author == user.user.id)
+        .or_not_found()?;
+
+    user_session::delete(&pool, session.id).await?;
+    cache::invalidate_user_session(&redis_pool, session.id as u64).await?;
+    revoke_token(session.token.as_str()).await?;
+
+    if Some(session.id) == user.session {
+        return ApiResponse::ok().del_cookie(COOKIE_NAME).finish();
+    }
+
+    ApiResponse::ok().finish()
+}
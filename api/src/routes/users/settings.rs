@@ -0,0 +1,38 @@
+use crate::db::{cache, PgPool, RedisPool};
+use crate::models::user_config::{self, EditUserConfig};
+use crate::models::Validate;
+use crate::routes::{ApiResponse, ApiResult};
+use crate::utils::auth::User;
+
+use actix_web::web::{Data, Json};
+use actix_web::{get, patch};
+
+#[get("/@me/settings")]
+pub async fn get_user_me_settings(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    let user_config = cache::get_user_config(&pool, &redis_pool, user.user.id as u64).await?;
+
+    ApiResponse::ok().data(user_config).finish()
+}
+
+#[patch("/@me/settings")]
+pub async fn patch_user_me_settings(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Json(new_settings): Json<EditUserConfig>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    new_settings.check()?;
+
+    let user_config = user_config::update(&pool, user.user.id, new_settings).await?;
+    cache::invalidate_user_config(&redis_pool, user.user.id as u64).await?;
+
+    ApiResponse::ok().data(user_config).finish()
+}
@@ -0,0 +1,90 @@
+use crate::constants::{USER_TOKEN_LENGTH, USER_TOKEN_MAX, USER_TOKEN_PREFIX};
+use crate::db::{PgPool, RedisPool};
+use crate::models::user_token::{self, NewUserToken};
+use crate::models::{string_int_opt, Validate};
+use crate::routes::{ApiResponse, ApiResult, OptionExt};
+use crate::utils::auth::User;
+
+use actix_web::web::{Data, Json, Path};
+use actix_web::{delete, get, post};
+use chrono::NaiveDateTime;
+use nanoid::nanoid;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Deserialize)]
+pub struct SimpleUserToken {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default, deserialize_with = "string_int_opt")]
+    pub guild: Option<u64>,
+    #[serde(default)]
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+#[get("/@me/tokens")]
+pub async fn get_user_me_tokens(user: User, pool: Data<PgPool>) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    let tokens = user_token::find_by_author(&pool, user.user.id).await?;
+
+    ApiResponse::ok().data(tokens).finish()
+}
+
+#[post("/@me/tokens")]
+pub async fn post_user_me_tokens(
+    user: User,
+    pool: Data<PgPool>,
+    redis_pool: Data<RedisPool>,
+    Json(new_token): Json<SimpleUserToken>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    if let Some(guild) = new_token.guild {
+        user.has_read_guild(&redis_pool, guild).await?;
+    }
+
+    let token = format!("{}{}", USER_TOKEN_PREFIX, nanoid!(USER_TOKEN_LENGTH));
+    let new_token = NewUserToken {
+        author: user.user.id,
+        name: new_token.name,
+        hash: user_token::hash_token(token.as_str()),
+        scopes: new_token.scopes,
+        guild: new_token.guild.map(|guild| guild as i64),
+        expires_at: new_token.expires_at,
+    };
+
+    new_token.check()?;
+
+    let tokens = user_token::find_by_author(&pool, user.user.id).await?;
+    if tokens.len() >= USER_TOKEN_MAX {
+        return ApiResponse::bad_request()
+            .message("error.token_limit")
+            .finish();
+    }
+
+    let user_token = user_token::create(&pool, new_token).await?;
+
+    let mut data = json!(user_token);
+    data["token"] = json!(token);
+
+    ApiResponse::ok().data(data).finish()
+}
+
+#[delete("/@me/tokens/{item}")]
+pub async fn delete_user_me_token(
+    user: User,
+    pool: Data<PgPool>,
+    Path(item): Path<u64>,
+) -> ApiResult<ApiResponse> {
+    user.is_session()?;
+
+    user_token::find(&pool, item as i64)
+        .await?
+        .filter(|token| token.author == user.user.id)
+        .or_not_found()?;
+
+    user_token::delete(&pool, item as i64).await?;
+
+    ApiResponse::ok().finish()
+}
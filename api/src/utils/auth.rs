@@ -1,29 +1,40 @@
 use crate::config::CONFIG;
 use crate::constants::{
-    csrf_token_key, guild_key, user_guilds_key, user_key, user_token_key, BOT_ADMIN_KEY,
-    BOT_OWNER_KEY, CALLBACK_PATH, COOKIE_NAME, CSRF_TOKEN_KEY_TTL, USER_GUILDS_KEY_TTL,
-    USER_KEY_TTL, USER_TOKEN_KEY_TTL,
+    csrf_token_key, guild_key, session_refresh_key, user_guilds_key, user_key, user_token_key,
+    BOT_ADMIN_KEY, BOT_OWNER_KEY, CALLBACK_PATH, COOKIE_NAME, CSRF_TOKEN_KEY_TTL,
+    GUILD_PERMISSION_KEY_TTL, SESSION_DEVICE_MAX, SESSION_MAX_AGE, SESSION_REFRESH_KEY_TTL,
+    SESSION_REFRESH_RETRY_MAX, SESSION_REFRESH_WAIT, USER_GUILDS_KEY_TTL, USER_KEY_TTL,
+    USER_TOKEN_KEY_TTL, USER_TOKEN_PREFIX,
 };
 use crate::db::cache::{self, get_blacklist_item};
-use crate::db::pubsub::models::{Connected, Member, Permission};
+use crate::db::pubsub::models::{self, Connected, Member};
 use crate::db::pubsub::Message;
 use crate::db::{PgPool, RedisPool};
-use crate::models::account::Account;
+use crate::models::account::{self, Account};
 use crate::models::guild::NewGuild;
+use crate::models::guild_permission::{self, Permission};
+use crate::models::user_session::{self, EditUserSession, NewUserSession, UserSession};
+use crate::models::user_token::{self, TokenScope, UserToken};
 use crate::routes::{ApiError, ApiResponse, ApiResult, OptionExt};
+use crate::utils::locale::Language;
+use crate::utils::signature::{self, get_header, SIGNATURE_PREFIX};
+use crate::utils::sleep;
 
 use actix_web::cookie::{Cookie, SameSite};
 use actix_web::dev::Payload;
 use actix_web::web::Data;
 use actix_web::{FromRequest, HttpMessage, HttpRequest};
 use chrono::Utc;
+use futures::future::try_join3;
 use http::header::AUTHORIZATION;
-use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, DecodingKey, EncodingKey, Header, Validation};
 use lazy_static::lazy_static;
 use nanoid::nanoid;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::future::Future;
 use std::pin::Pin;
@@ -56,6 +67,46 @@ lazy_static! {
         .unwrap()
     };
     static ref OAUTH_SCOPES: Vec<Scope> = vec![Scope::Identify, Scope::Guilds];
+    static ref SESSION_KEYS: Vec<SessionKey> = CONFIG
+        .session_keys
+        .split(',')
+        .map(|key| {
+            let mut parts = key.trim().splitn(2, ':');
+            let id = parts.next().unwrap_or_default();
+            let secret = parts
+                .next()
+                .filter(|_| !id.is_empty())
+                .unwrap_or_else(|| panic!("Invalid session key: {}", id));
+
+            SessionKey {
+                id: id.to_owned(),
+                encoding: EncodingKey::from_base64_secret(secret)
+                    .unwrap_or_else(|_| panic!("Invalid session key: {}", id)),
+                decoding: DecodingKey::from_base64_secret(secret)
+                    .unwrap_or_else(|_| panic!("Invalid session key: {}", id)),
+            }
+        })
+        .collect();
+}
+
+struct SessionKey {
+    id: String,
+    encoding: EncodingKey,
+    decoding: DecodingKey<'static>,
+}
+
+fn get_signing_key() -> &'static SessionKey {
+    &SESSION_KEYS[0]
+}
+
+fn get_verifying_key(token: &str) -> Option<&'static SessionKey> {
+    let id = decode_header(token).ok()?.kid?;
+
+    SESSION_KEYS.iter().find(|key| key.id == id)
+}
+
+pub fn init_session_keys() {
+    lazy_static::initialize(&SESSION_KEYS);
 }
 
 pub async fn get_csrf_redirect(pool: &RedisPool, token: &str) -> ApiResult<Option<String>> {
@@ -107,10 +158,18 @@ pub fn get_invite_uri(guild: Option<u64>) -> ApiResult<String> {
     Ok(uri.build().replace("%20", "+"))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct ResolvedPermissions {
+    is_member: bool,
+    is_owner: bool,
+    permissions: HashSet<Permission>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
-    token: String,
+    sid: i64,
     iat: u64,
+    exp: u64,
 }
 
 pub async fn token_exchange(code: &str) -> ApiResult<AccessTokenExchangeResponse> {
@@ -140,24 +199,101 @@ pub async fn token_exchange(code: &str) -> ApiResult<AccessTokenExchangeResponse
     Ok(response)
 }
 
-pub async fn get_token_cookie(exchange: AccessTokenExchangeResponse) -> ApiResult<Cookie<'static>> {
+pub async fn token_refresh(refresh_token: &str) -> ApiResult<Option<AccessTokenExchangeResponse>> {
+    let uri = Client::BASE_URI.replace("/authorize", "/token");
+
+    let mut params = HashMap::new();
+    params.insert("client_id".to_owned(), OAUTH_CLIENT.client_id().to_string());
+    params.insert(
+        "client_secret".to_owned(),
+        OAUTH_CLIENT.client_secret().to_string(),
+    );
+    params.insert("grant_type".to_owned(), "refresh_token".to_owned());
+    params.insert("refresh_token".to_owned(), refresh_token.to_owned());
+
+    let response = reqwest::Client::new()
+        .post(uri.as_str())
+        .form(&params)
+        .send()
+        .await?;
+
+    if response.status() != StatusCode::BAD_REQUEST {
+        return Ok(Some(response.error_for_status()?.json().await?));
+    }
+
+    let error: Value = response.json().await?;
+    if error["error"] == "invalid_grant" {
+        Ok(None)
+    } else {
+        Err(ApiResponse::internal_server_error().into())
+    }
+}
+
+pub async fn revoke_token(token: &str) -> ApiResult<()> {
+    let uri = Client::BASE_URI.replace("/authorize", "/token/revoke");
+
+    let mut params = HashMap::new();
+    params.insert("client_id".to_owned(), OAUTH_CLIENT.client_id().to_string());
+    params.insert(
+        "client_secret".to_owned(),
+        OAUTH_CLIENT.client_secret().to_string(),
+    );
+    params.insert("token".to_owned(), token.to_owned());
+
+    reqwest::Client::new()
+        .post(uri.as_str())
+        .form(&params)
+        .send()
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_token_cookie(
+    pool: &PgPool,
+    redis_pool: &RedisPool,
+    exchange: AccessTokenExchangeResponse,
+    device: &str,
+) -> ApiResult<Cookie<'static>> {
     let url = Url::parse(BASE_URI.as_str())?;
     let domain = url.domain().unwrap_or_default().to_owned();
 
+    let user = User::from_token(redis_pool, exchange.access_token.as_str()).await?;
+    user_session::delete_expired_by_author(pool, user.user.id).await?;
+
+    let now = Utc::now().naive_utc();
+    let session = user_session::create(
+        pool,
+        NewUserSession {
+            author: user.user.id,
+            token: exchange.access_token,
+            refresh_token: exchange.refresh_token,
+            token_expires_at: now + chrono::Duration::seconds(exchange.expires_in as i64),
+            device: device.chars().take(SESSION_DEVICE_MAX).collect(),
+            expires_at: now + chrono::Duration::seconds(SESSION_MAX_AGE),
+        },
+    )
+    .await?;
+
+    let key = get_signing_key();
     let token = encode(
-        &Header::default(),
+        &Header {
+            kid: Some(key.id.clone()),
+            ..Default::default()
+        },
         &Claims {
-            token: exchange.access_token,
-            iat: Utc::now().timestamp_millis() as u64,
+            sid: session.id,
+            iat: now.timestamp() as u64,
+            exp: session.expires_at.timestamp() as u64,
         },
-        &EncodingKey::from_base64_secret(CONFIG.api_secret.as_str())?,
+        &key.encoding,
     )?;
 
     let cookie = Cookie::build(COOKIE_NAME, token)
         .domain(domain)
         .http_only(true)
         .max_age(
-            Duration::from_secs(exchange.expires_in)
+            Duration::from_secs(SESSION_MAX_AGE as u64)
                 .try_into()
                 .unwrap_or_default(),
         )
@@ -172,6 +308,8 @@ pub struct User {
     pub token: String,
     pub user: Account,
     pub is_bot: bool,
+    pub api_token: Option<UserToken>,
+    pub session: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -197,6 +335,8 @@ impl User {
                     token: token.to_owned(),
                     user,
                     is_bot: false,
+                    api_token: None,
+                    session: None,
                 });
             }
         }
@@ -223,9 +363,111 @@ impl User {
             token: token.to_owned(),
             user,
             is_bot: false,
+            api_token: None,
+            session: None,
         })
     }
 
+    async fn from_session(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        id: i64,
+    ) -> ApiResult<Option<Self>> {
+        let session = cache::get_user_session(pool, redis_pool, id as u64).await?;
+
+        let mut session = match session {
+            Some(session) if !session.is_expired() => session,
+            _ => return Ok(None),
+        };
+
+        if session.needs_refresh() {
+            session = match Self::refresh_session(pool, redis_pool, session).await? {
+                Some(session) => session,
+                None => return Ok(None),
+            };
+        }
+
+        if session.is_seen_stale() {
+            session = user_session::update_last_seen(pool, id).await?;
+            cache::invalidate_user_session(redis_pool, id as u64).await?;
+        }
+
+        let mut user = Self::from_token(redis_pool, session.token.as_str()).await?;
+        user.session = Some(session.id);
+
+        Ok(Some(user))
+    }
+
+    async fn refresh_session(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        session: UserSession,
+    ) -> ApiResult<Option<UserSession>> {
+        let key = session_refresh_key(session.id as u64);
+
+        if !cache::set_nx_and_expire(redis_pool, key.as_str(), &true, SESSION_REFRESH_KEY_TTL)
+            .await?
+        {
+            if !session.is_token_expired() {
+                return Ok(Some(session));
+            }
+
+            for _ in 0..SESSION_REFRESH_RETRY_MAX {
+                sleep(Duration::from_millis(SESSION_REFRESH_WAIT as u64)).await?;
+
+                match user_session::find(pool, session.id).await? {
+                    Some(current) if !current.is_token_expired() => return Ok(Some(current)),
+                    Some(_) => continue,
+                    None => return Ok(None),
+                }
+            }
+
+            return Err(ApiResponse::service_unavailable().into());
+        }
+
+        let res = Self::refresh_token(pool, redis_pool, session).await;
+        cache::del(redis_pool, key).await?;
+
+        res
+    }
+
+    async fn refresh_token(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        session: UserSession,
+    ) -> ApiResult<Option<UserSession>> {
+        let session = match user_session::find(pool, session.id).await? {
+            Some(session) if session.needs_refresh() => session,
+            session => return Ok(session),
+        };
+
+        let exchange = match token_refresh(session.refresh_token.as_str()).await {
+            Ok(Some(exchange)) => exchange,
+            Ok(None) => {
+                user_session::delete(pool, session.id).await?;
+                cache::invalidate_user_session(redis_pool, session.id as u64).await?;
+                return Ok(None);
+            },
+            Err(_) if !session.is_token_expired() => return Ok(Some(session)),
+            Err(err) => return Err(err),
+        };
+
+        let session = user_session::update(
+            pool,
+            session.id,
+            EditUserSession {
+                token: exchange.access_token,
+                refresh_token: exchange.refresh_token,
+                token_expires_at: Utc::now().naive_utc()
+                    + chrono::Duration::seconds(exchange.expires_in as i64),
+            },
+        )
+        .await?;
+        cache::invalidate_user_session(redis_pool, session.id as u64).await?;
+
+        Ok(Some(session))
+    }
+
     async fn from_bot(
         pool: &RedisPool,
         id: &str,
@@ -246,25 +488,69 @@ impl User {
             token: "".to_owned(),
             user,
             is_bot: true,
+            api_token: None,
+            session: None,
         })
     }
 
-    pub async fn revoke_token(self) -> ApiResult<()> {
-        let uri = Client::BASE_URI.replace("/authorize", "/token/revoke");
-
-        let mut params = HashMap::new();
-        params.insert("client_id".to_owned(), OAUTH_CLIENT.client_id().to_string());
-        params.insert(
-            "client_secret".to_owned(),
-            OAUTH_CLIENT.client_secret().to_string(),
-        );
-        params.insert("token".to_owned(), self.token);
-
-        reqwest::Client::new()
-            .post(uri.as_str())
-            .form(&params)
-            .send()
-            .await?;
+    async fn from_api_token(
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+        token: &str,
+    ) -> ApiResult<Option<Self>> {
+        let api_token = user_token::find_by_hash(pool, user_token::hash_token(token))
+            .await?
+            .filter(|api_token| !api_token.is_expired());
+
+        let api_token = match api_token {
+            Some(api_token) => api_token,
+            None => return Ok(None),
+        };
+
+        let user: Option<Account> = cache::get(redis_pool, user_key(api_token.author as u64))
+            .await
+            .unwrap_or(None);
+        let user = match user {
+            Some(user) => user,
+            None => match account::find(pool, api_token.author).await? {
+                Some(user) => user,
+                None => return Ok(None),
+            },
+        };
+
+        user_token::update_last_used(pool, api_token.id).await?;
+
+        Ok(Some(Self {
+            token: "".to_owned(),
+            user,
+            is_bot: false,
+            api_token: Some(api_token),
+            session: None,
+        }))
+    }
+
+    async fn set_language(
+        &self,
+        req: &HttpRequest,
+        pool: &PgPool,
+        redis_pool: &RedisPool,
+    ) -> ApiResult<()> {
+        let user_config = cache::get_user_config(pool, redis_pool, self.user.id as u64).await?;
+
+        if !user_config.language.is_empty() {
+            req.extensions_mut().insert(Language(user_config.language));
+        }
+
+        Ok(())
+    }
+
+    pub async fn revoke_session(self, pool: &PgPool, redis_pool: &RedisPool) -> ApiResult<()> {
+        if let Some(session) = self.session {
+            user_session::delete(pool, session).await?;
+            cache::invalidate_user_session(redis_pool, session as u64).await?;
+        }
+
+        revoke_token(self.token.as_str()).await?;
 
         Ok(())
     }
@@ -327,12 +613,12 @@ impl User {
                         Ok(())
                     } else {
                         Err(ApiResponse::bad_request()
-                            .message("You need to be connected to the same channel as the bot.")
+                            .message("error.not_same_channel")
                             .into())
                     }
                 } else {
                     Err(ApiResponse::bad_request()
-                        .message("You need to be connected to a channel.")
+                        .message("error.not_connected")
                         .into())
                 }
             } else {
@@ -340,20 +626,38 @@ impl User {
             }
         } else {
             Err(ApiResponse::bad_request()
-                .message("The bot is not connected to any channel.")
+                .message("error.bot_not_connected")
                 .into())
         }
     }
 
-    pub fn is_not_bot(&self) -> ApiResult<()> {
-        if !self.is_bot {
+    pub fn is_session(&self) -> ApiResult<()> {
+        if !self.is_bot && self.api_token.is_none() {
             Ok(())
         } else {
             Err(ApiResponse::forbidden().into())
         }
     }
 
+    pub fn has_scope(&self, scope: TokenScope, guild: Option<u64>) -> ApiResult<()> {
+        if let Some(api_token) = &self.api_token {
+            if !api_token.has_scope(scope) {
+                return Err(ApiResponse::forbidden().into());
+            }
+
+            if api_token.guild.is_some() && api_token.guild != guild.map(|guild| guild as i64) {
+                return Err(ApiResponse::forbidden().into());
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn has_bot_owner(&self, pool: &RedisPool) -> ApiResult<()> {
+        if self.api_token.is_some() {
+            return Err(ApiResponse::forbidden().into());
+        }
+
         if cache::sismember(pool, BOT_OWNER_KEY, &self.user.id).await? {
             Ok(())
         } else {
@@ -362,6 +666,10 @@ impl User {
     }
 
     pub async fn has_bot_admin(&self, pool: &RedisPool) -> ApiResult<()> {
+        if self.api_token.is_some() {
+            return Err(ApiResponse::forbidden().into());
+        }
+
         if self.has_bot_owner(pool).await.is_ok() {
             return Ok(());
         }
@@ -373,109 +681,124 @@ impl User {
         Err(ApiResponse::forbidden().into())
     }
 
-    pub async fn has_manage_guild(
-        &self,
-        pool: &PgPool,
-        redis_pool: &RedisPool,
-        guild: u64,
-    ) -> ApiResult<()> {
-        let member = self.get_member(redis_pool, guild).await?;
-        let config = cache::get_config(pool, redis_pool, guild).await?;
-
-        if self.has_bot_owner(redis_pool).await.is_ok() {
-            return Ok(());
-        }
-
-        if config
-            .guild_roles
-            .iter()
-            .any(|role| *role as u64 == guild || member.roles.contains(role))
-        {
-            return Ok(());
-        }
-
-        let permission = Message::get_permission(guild, self.user.id as u64, None)
-            .send_and_wait::<Permission>(redis_pool)
-            .await?
-            .or_not_found()?
-            .permission;
+    fn has_permission_scope(&self, permission: Permission, guild: u64) -> ApiResult<()> {
+        let scope = match permission {
+            Permission::ManageGuild => None,
+            Permission::ManagePlaylist => Some(TokenScope::Playlist),
+            Permission::LoadPlaylist
+            | Permission::AddTrack
+            | Permission::RemoveTrack
+            | Permission::MoveTrack
+            | Permission::ClearQueue
+            | Permission::Shuffle => Some(TokenScope::Queue),
+            Permission::Pause
+            | Permission::Skip
+            | Permission::Seek
+            | Permission::Volume
+            | Permission::Filters
+            | Permission::Loop
+            | Permission::Disconnect => Some(TokenScope::Player),
+        };
 
-        if let Some(permissions) = Permissions::from_bits(permission as u64) {
-            if permissions.contains(Permissions::MANAGE_GUILD) {
-                Ok(())
-            } else {
-                Err(ApiResponse::forbidden().into())
-            }
-        } else {
-            Err(ApiResponse::forbidden().into())
+        match scope {
+            Some(scope) => self.has_scope(scope, Some(guild)),
+            None if self.api_token.is_some() => Err(ApiResponse::forbidden().into()),
+            None => Ok(()),
         }
     }
 
-    pub async fn has_manage_playlist(
+    async fn resolve_permissions(
         &self,
         pool: &PgPool,
         redis_pool: &RedisPool,
         guild: u64,
-    ) -> ApiResult<()> {
-        let member = self.get_member(redis_pool, guild).await?;
-        let config = cache::get_config(pool, redis_pool, guild).await?;
+    ) -> ApiResult<ResolvedPermissions> {
+        let (member, is_owner, permission) = try_join3(
+            Message::get_member(guild, self.user.id as u64).send_and_wait::<Member>(redis_pool),
+            cache::sismember(redis_pool, BOT_OWNER_KEY, &self.user.id),
+            Message::get_permission(guild, self.user.id as u64, None)
+                .send_and_wait::<models::Permission>(redis_pool),
+        )
+        .await?;
 
-        if self.has_manage_guild(pool, redis_pool, guild).await.is_ok() {
-            return Ok(());
-        }
+        let member = match member {
+            Some(member) => member,
+            None => {
+                return Ok(ResolvedPermissions {
+                    is_member: false,
+                    is_owner,
+                    permissions: HashSet::new(),
+                })
+            },
+        };
 
-        if config
-            .playlist_roles
-            .iter()
-            .any(|role| *role as u64 == guild || member.roles.contains(role))
-        {
-            Ok(())
+        let is_manager = permission
+            .and_then(|permission| Permissions::from_bits(permission.permission as u64))
+            .map(|permissions| permissions.contains(Permissions::MANAGE_GUILD))
+            .unwrap_or(false);
+
+        let permissions = if is_manager {
+            Permission::ALL.iter().copied().collect()
         } else {
-            Err(ApiResponse::forbidden().into())
-        }
+            let overrides = guild_permission::find_by_guild(pool, guild as i64).await?;
+            guild_permission::resolve(
+                overrides.as_slice(),
+                guild,
+                self.user.id as u64,
+                member.roles.as_slice(),
+            )
+        };
+
+        Ok(ResolvedPermissions {
+            is_member: true,
+            is_owner,
+            permissions,
+        })
     }
 
-    pub async fn has_manage_player(
+    pub async fn get_permissions(
         &self,
         pool: &PgPool,
         redis_pool: &RedisPool,
         guild: u64,
-    ) -> ApiResult<()> {
-        let member = self.get_member(redis_pool, guild).await?;
-        let config = cache::get_config(pool, redis_pool, guild).await?;
-
-        if self.has_manage_guild(pool, redis_pool, guild).await.is_ok() {
-            return Ok(());
-        }
+    ) -> ApiResult<HashSet<Permission>> {
+        let key = cache::get_guild_permission_key(redis_pool, guild, self.user.id as u64).await?;
+        let resolved: Option<ResolvedPermissions> = cache::get(redis_pool, key.as_str()).await?;
+
+        let resolved = match resolved {
+            Some(resolved) => resolved,
+            None => {
+                let resolved = self.resolve_permissions(pool, redis_pool, guild).await?;
+                cache::set_and_expire(redis_pool, key, &resolved, GUILD_PERMISSION_KEY_TTL).await?;
+                resolved
+            },
+        };
 
-        if config
-            .player_roles
-            .iter()
-            .any(|role| *role as u64 == guild || member.roles.contains(role))
-        {
-            Ok(())
+        let is_owner = resolved.is_member && resolved.is_owner;
+        let mut permissions = if is_owner && self.api_token.is_none() {
+            Permission::ALL.iter().copied().collect()
         } else {
-            Err(ApiResponse::forbidden().into())
-        }
+            resolved.permissions
+        };
+
+        permissions.retain(|permission| self.has_permission_scope(*permission, guild).is_ok());
+
+        Ok(permissions)
     }
 
-    pub async fn has_manage_queue(
+    pub async fn has_permission(
         &self,
         pool: &PgPool,
         redis_pool: &RedisPool,
         guild: u64,
+        permission: Permission,
     ) -> ApiResult<()> {
-        let member = self.get_member(redis_pool, guild).await?;
-        let config = cache::get_config(pool, redis_pool, guild).await?;
+        self.has_permission_scope(permission, guild)?;
 
-        if self.has_manage_guild(pool, redis_pool, guild).await.is_ok() {
-            return Ok(());
-        }
-
-        if config
-            .queue_roles
-            .iter()
-            .any(|role| *role as u64 == guild || member.roles.contains(role))
+        if self
+            .get_permissions(pool, redis_pool, guild)
+            .await?
+            .contains(&permission)
         {
             Ok(())
         } else {
@@ -483,31 +806,16 @@ impl User {
         }
     }
 
-    pub async fn has_manage_track(
-        &self,
-        pool: &PgPool,
-        redis_pool: &RedisPool,
-        guild: u64,
-    ) -> ApiResult<()> {
-        let member = self.get_member(redis_pool, guild).await?;
-        let config = cache::get_config(pool, redis_pool, guild).await?;
+    pub async fn has_read_guild(&self, pool: &RedisPool, guild: u64) -> ApiResult<()> {
+        self.has_scope(TokenScope::Read, Some(guild))?;
+
+        let key = cache::get_guild_permission_key(pool, guild, self.user.id as u64).await?;
+        let resolved: Option<ResolvedPermissions> = cache::get(pool, key).await?;
 
-        if self.has_manage_guild(pool, redis_pool, guild).await.is_ok() {
+        if resolved.map(|resolved| resolved.is_member).unwrap_or(false) {
             return Ok(());
         }
 
-        if config
-            .track_roles
-            .iter()
-            .any(|role| *role as u64 == guild || member.roles.contains(role))
-        {
-            Ok(())
-        } else {
-            Err(ApiResponse::forbidden().into())
-        }
-    }
-
-    pub async fn has_read_guild(&self, pool: &RedisPool, guild: u64) -> ApiResult<()> {
         if self.has_bot_admin(pool).await.is_ok() {
             Message::get_guild(guild)
                 .send_and_pause(pool)
@@ -529,62 +837,90 @@ impl FromRequest for User {
     type Config = ();
 
     fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let db_pool = Data::<PgPool>::from_request(req, payload).into_inner();
         let pool = Data::<RedisPool>::from_request(req, payload).into_inner();
         let cookie: Option<Cookie<'static>> = req.cookie(COOKIE_NAME);
         let headers = req.headers().clone();
+        let req = req.clone();
 
         Box::pin(async move {
+            let db_pool = db_pool?;
             let pool = pool?;
 
             if let Some(cookie) = cookie {
-                if let Ok(token) = decode::<Claims>(
-                    cookie.value(),
-                    &DecodingKey::from_base64_secret(CONFIG.api_secret.as_str())?,
-                    &Validation::default(),
-                ) {
-                    let user = Self::from_token(&pool, token.claims.token.as_str()).await?;
-                    if get_blacklist_item(&pool, user.user.id as u64)
-                        .await?
-                        .is_some()
+                let token = get_verifying_key(cookie.value()).and_then(|key| {
+                    decode::<Claims>(cookie.value(), &key.decoding, &Validation::default()).ok()
+                });
+
+                if let Some(token) = token {
+                    if let Some(user) =
+                        Self::from_session(&db_pool, &pool, token.claims.sid).await?
                     {
-                        return Err(ApiResponse::forbidden()
-                            .message("You are banned from the bot.")
-                            .into());
-                    }
+                        if get_blacklist_item(&pool, user.user.id as u64)
+                            .await?
+                            .is_some()
+                        {
+                            return Err(ApiResponse::forbidden().message("error.banned").into());
+                        }
 
-                    return Ok(user);
+                        user.set_language(&req, &db_pool, &pool).await?;
+
+                        return Ok(user);
+                    }
                 }
 
                 return Err(ApiResponse::unauthorized()
-                    .message("Your session is invalid, please login again.")
+                    .message("error.session_invalid")
                     .del_cookie(COOKIE_NAME)
                     .into());
             }
 
             if let Some(auth) = headers.get(AUTHORIZATION) {
-                if auth.to_str()? == CONFIG.api_secret.as_str() {
-                    let id = headers
-                        .get("User-Id")
-                        .map(|value| value.to_str())
-                        .transpose()?
-                        .unwrap_or_default();
-                    let username = headers
-                        .get("User-Username")
-                        .map(|value| value.to_str())
-                        .transpose()?
-                        .unwrap_or_default();
-                    let discriminator = headers
-                        .get("User-Discriminator")
-                        .map(|value| value.to_str())
-                        .transpose()?
-                        .unwrap_or_default();
-                    let avatar = headers
-                        .get("User-Avatar")
-                        .map(|value| value.to_str())
-                        .transpose()?
-                        .unwrap_or_default();
-
-                    let user = Self::from_bot(&pool, id, username, discriminator, avatar).await?;
+                if let Some(token) = auth.to_str()?.strip_prefix("Bearer ") {
+                    if !token.starts_with(USER_TOKEN_PREFIX) {
+                        return Err(ApiResponse::unauthorized()
+                            .message("error.token_invalid")
+                            .into());
+                    }
+
+                    let user = match Self::from_api_token(&db_pool, &pool, token).await? {
+                        Some(user) => user,
+                        None => {
+                            return Err(ApiResponse::unauthorized()
+                                .message("error.token_invalid")
+                                .into())
+                        },
+                    };
+                    if get_blacklist_item(&pool, user.user.id as u64)
+                        .await?
+                        .is_some()
+                    {
+                        return Err(ApiResponse::forbidden().message("error.banned").into());
+                    }
+
+                    user.set_language(&req, &db_pool, &pool).await?;
+
+                    return Ok(user);
+                }
+
+                let auth = auth.to_str()?;
+                let is_bot = if let Some(signature) = auth.strip_prefix(SIGNATURE_PREFIX) {
+                    signature::verify(&pool, &req, signature).await?;
+                    true
+                } else {
+                    CONFIG.api_legacy_auth && auth == CONFIG.api_secret.as_str()
+                };
+
+                if is_bot {
+                    let user = Self::from_bot(
+                        &pool,
+                        get_header(&headers, "User-Id")?,
+                        get_header(&headers, "User-Username")?,
+                        get_header(&headers, "User-Discriminator")?,
+                        get_header(&headers, "User-Avatar")?,
+                    )
+                    .await?;
+                    user.set_language(&req, &db_pool, &pool).await?;
 
                     return Ok(user);
                 }
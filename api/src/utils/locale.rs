@@ -0,0 +1,163 @@
+use actix_web::HttpRequest;
+use http::header::ACCEPT_LANGUAGE;
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+lazy_static! {
+    static ref CATALOGUES: HashMap<&'static str, HashMap<String, String>> = vec![
+        ("en", include_str!("../../locales/en.json")),
+        ("de", include_str!("../../locales/de.json")),
+    ]
+    .into_iter()
+    .map(|(language, content)| (language, serde_json::from_str(content).unwrap()))
+    .collect();
+}
+
+#[derive(Debug, Clone)]
+pub struct Language(pub String);
+
+#[derive(Debug, Clone)]
+enum Arg {
+    Text(String),
+    Key(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Translation {
+    key: String,
+    args: Vec<(String, Arg)>,
+}
+
+impl Translation {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            args: vec![],
+        }
+    }
+
+    pub fn arg(mut self, name: &str, value: impl Display) -> Self {
+        self.args
+            .push((name.to_owned(), Arg::Text(value.to_string())));
+        self
+    }
+
+    pub fn arg_key(mut self, name: &str, key: &str) -> Self {
+        self.args.push((name.to_owned(), Arg::Key(key.to_owned())));
+        self
+    }
+
+    pub fn render(&self, language: &str) -> String {
+        let content = translate(language, self.key.as_str());
+        let mut rendered = String::with_capacity(content.len());
+        let mut rest = content.as_str();
+
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let end = match rest.find('}') {
+                Some(end) => end,
+                None => break,
+            };
+
+            let name = &rest[1..end];
+            match self.args.iter().find(|(arg, _)| arg == name) {
+                Some((_, Arg::Text(value))) => rendered.push_str(value),
+                Some((_, Arg::Key(key))) => rendered.push_str(translate(language, key).as_str()),
+                None => rendered.push_str(&rest[..=end]),
+            }
+
+            rest = &rest[end + 1..];
+        }
+
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+impl From<&str> for Translation {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+pub fn is_supported(language: &str) -> bool {
+    CATALOGUES.contains_key(language)
+}
+
+pub fn translate(language: &str, key: &str) -> String {
+    CATALOGUES
+        .get(language)
+        .and_then(|catalogue| catalogue.get(key))
+        .or_else(|| {
+            CATALOGUES
+                .get(DEFAULT_LANGUAGE)
+                .and_then(|catalogue| catalogue.get(key))
+        })
+        .cloned()
+        .unwrap_or_else(|| key.to_owned())
+}
+
+pub fn get_language(req: &HttpRequest) -> String {
+    if let Some(language) = req.extensions().get::<Language>() {
+        return language.0.clone();
+    }
+
+    req.headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| {
+            value
+                .split(',')
+                .filter_map(|item| item.split(';').next())
+                .filter_map(|item| item.trim().split('-').next())
+                .map(|item| item.to_lowercase())
+                .find(|item| is_supported(item.as_str()))
+        })
+        .unwrap_or_else(|| DEFAULT_LANGUAGE.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_args() {
+        let rendered = Translation::new("log.queue_add")
+            .arg("track", "Song")
+            .render("en");
+
+        assert_eq!(rendered, "Added Song to the queue.");
+    }
+
+    #[test]
+    fn render_key_args() {
+        let rendered = Translation::new("log.playlist_add")
+            .arg_key("playlist", "origin.system")
+            .render("de");
+
+        assert_eq!(rendered, "Eine Playlist erstellt (Automatisch).");
+    }
+
+    #[test]
+    fn render_missing_args() {
+        let rendered = Translation::new("log.queue_add").render("en");
+
+        assert_eq!(rendered, "Added {track} to the queue.");
+    }
+
+    #[test]
+    fn render_fallback() {
+        assert_eq!(
+            Translation::new("log.queue_add")
+                .arg("track", "{x}")
+                .render("fr"),
+            "Added {x} to the queue."
+        );
+        assert_eq!(Translation::new("unknown.key").render("en"), "unknown.key");
+    }
+}
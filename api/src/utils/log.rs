@@ -1,16 +1,21 @@
+use crate::config::CONFIG;
 use crate::db::pubsub::Message;
 use crate::db::{cache, PgPool, RedisPool};
-use crate::models::config::EditConfig;
-use crate::models::guild_log::{self, NewGuildLog};
+use crate::models::config::{Config, EditConfig};
+use crate::models::guild_log::{self, GuildLog, LogCategory, LogKind, LogOrigin, NewGuildLog};
+use crate::models::guild_webhook::{self, GuildWebhook};
 use crate::models::playlist::{EditPlaylist, NewPlaylist, Playlist};
+use crate::models::playlist_item::PlaylistItem;
 use crate::routes::guilds::{SimplePlayer, SimplePosition};
 use crate::routes::ApiResult;
 use crate::utils::auth::User;
+use crate::utils::locale::{self, Translation};
 use crate::utils::queue::{self, QueueItem};
-use crate::utils::{format_duration, format_track};
+use crate::utils::{format_duration, webhook};
 
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Map, Value};
+use std::str::FromStr;
 use twilight_andesite::model::{TrackException, TrackStuck, WebsocketClose};
 
 #[derive(Debug)]
@@ -23,15 +28,19 @@ pub enum LogInfo {
     PlayerRemove(u64),
     PlayerUpdate(SimplePlayer),
     QueueAdd(QueueItem),
+    QueueAddFavourites(u64),
     QueueRemove(QueueItem),
     QueueShift(QueueItem, SimplePosition),
     QueueClear(Vec<QueueItem>),
     QueueShuffle,
     PlaylistAdd(NewPlaylist),
     PlaylistRemove(Playlist),
-    PlaylistUpdate(EditPlaylist),
+    PlaylistUpdate(Playlist, EditPlaylist),
     PlaylistLoad(Playlist, u64),
-    SettingsUpdate(EditConfig),
+    PlaylistItemAdd(Playlist, u64),
+    PlaylistItemRemove(Playlist, PlaylistItem),
+    PlaylistItemShift(Playlist, PlaylistItem, SimplePosition),
+    SettingsUpdate(Config, EditConfig),
 }
 
 #[derive(Debug, Deserialize)]
@@ -45,6 +54,107 @@ enum PlayerUpdateInfo {
     Filters,
 }
 
+impl FromStr for PlayerUpdateInfo {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderedLog {
+    #[serde(flatten)]
+    pub log: GuildLog,
+    pub action: String,
+}
+
+impl RenderedLog {
+    pub fn new(log: GuildLog, language: &str) -> Self {
+        let action = render(language, log.kind.as_str(), &log.payload, false);
+        Self { log, action }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Csv,
+}
+
+impl FromStr for LogFormat {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl LogFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+fn escape_csv(value: &str) -> String {
+    let value = if value.starts_with(&['=', '+', '-', '@'][..]) {
+        format!("'{}", value)
+    } else {
+        value.to_owned()
+    };
+
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+pub fn export(format: LogFormat, logs: Vec<GuildLog>, language: &str) -> ApiResult<String> {
+    let content = match format {
+        LogFormat::Json => serde_json::to_string_pretty(
+            &logs
+                .into_iter()
+                .map(|log| RenderedLog::new(log, language))
+                .collect::<Vec<_>>(),
+        )?,
+        LogFormat::Csv => {
+            let mut content = "id,created_at,author,origin,kind,action,payload\n".to_owned();
+            for log in logs {
+                content.push_str(
+                    format!(
+                        "{},{},{},{},{},{},{}\n",
+                        log.id,
+                        log.created_at.format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+                        log.author,
+                        log.origin,
+                        log.kind,
+                        escape_csv(
+                            render(language, log.kind.as_str(), &log.payload, false).as_str()
+                        ),
+                        escape_csv(log.payload.to_string().as_str())
+                    )
+                    .as_str(),
+                );
+            }
+            content
+        },
+    };
+
+    Ok(content)
+}
+
 fn get_updates(value: impl Serialize) -> Vec<(String, Value)> {
     let value = serde_json::to_value(value);
 
@@ -69,162 +179,385 @@ fn has_update(value: impl Serialize) -> bool {
     !get_updates(value).is_empty()
 }
 
-pub async fn register(
-    pool: &PgPool,
-    redis_pool: &RedisPool,
-    guild: u64,
-    user: User,
-    info: LogInfo,
-) -> ApiResult<()> {
-    let mut title = "";
+fn get_changes(old: impl Serialize, new: impl Serialize) -> Map<String, Value> {
+    let old = serde_json::to_value(old).unwrap_or_default();
 
-    let player_message = match &info {
-        LogInfo::PlayerAdd(id) => {
-            title = "Player Connected";
-            format!("Connected to the channel <#{}>.", id)
-        },
-        LogInfo::PlayerRemove(id) => {
-            title = "Player Disconnected";
-            format!("Disconnected from the channel <#{}>.", id)
-        },
-        LogInfo::PlayerUpdate(player) if has_update(&player) => {
-            title = "Player Updated";
+    get_updates(new)
+        .into_iter()
+        .filter(|(key, value)| old.get(key) != Some(value))
+        .map(|(key, value)| {
+            let change = json!({ "old": old.get(&key), "new": value });
+            (key, change)
+        })
+        .collect()
+}
 
-            let (key, value) = get_updates(&player).remove(0);
+fn format_changes(changes: &Value) -> String {
+    changes
+        .as_object()
+        .map(|changes| {
+            changes
+                .iter()
+                .map(|(key, change)| format!("{}: {} -> {}", key, change["old"], change["new"]))
+                .collect::<Vec<String>>()
+                .join(", ")
+        })
+        .unwrap_or_default()
+}
 
-            match serde_json::from_str(format!("\"{}\"", key).as_str())? {
-                PlayerUpdateInfo::Looping => {
-                    format!("Set the player loop to {}.", value)
-                },
-                PlayerUpdateInfo::Playing => {
-                    format!(
-                        "Set the playing track to {}.",
-                        format_track(
-                            &queue::get_track(
-                                redis_pool,
-                                guild,
-                                value.as_i64().unwrap_or_default() as i32,
-                            )
-                            .await?
-                            .unwrap_or_default()
-                        )
-                    )
-                },
-                PlayerUpdateInfo::Position => {
-                    format!(
-                        "Set the player position to {}.",
-                        format_duration(value.as_u64().unwrap_or_default())
-                    )
+fn get_title(language: &str, kind: LogKind) -> String {
+    locale::translate(language, format!("title.{}", kind.as_str()).as_str())
+}
+
+fn get_origin(language: &str, origin: LogOrigin) -> String {
+    locale::translate(language, format!("origin.{}", origin.as_str()).as_str())
+}
+
+pub fn render(language: &str, kind: &str, payload: &Value, discord: bool) -> String {
+    let text = |key: &str| payload[key].as_str().unwrap_or_default().to_owned();
+    let track = || {
+        let uri = text("uri");
+        if discord && !uri.is_empty() {
+            format!("[{}]({})", text("track"), uri)
+        } else {
+            text("track")
+        }
+    };
+    let channel = || {
+        if discord {
+            format!("<#{}>", payload["channel"])
+        } else {
+            payload["channel"].to_string()
+        }
+    };
+
+    let translation = match kind.parse::<LogKind>() {
+        Ok(LogKind::Legacy) => return text("action"),
+        Ok(LogKind::NowPlaying) if discord => Translation::new("log.now_playing_message")
+            .arg("track", track())
+            .arg("author", &payload["author"]),
+        Ok(LogKind::NowPlaying) => Translation::new("log.now_playing").arg("track", track()),
+        Ok(LogKind::TrackStuck) => {
+            Translation::new("log.track_stuck").arg("threshold", &payload["threshold_ms"])
+        },
+        Ok(LogKind::TrackException) => {
+            Translation::new("log.track_exception").arg("error", text("error"))
+        },
+        Ok(LogKind::WebsocketClose) => {
+            let reason = text("reason");
+            let reason = reason.trim_end_matches('.');
+            if reason.is_empty() {
+                Translation::new("log.websocket_close").arg("code", &payload["code"])
+            } else {
+                Translation::new("log.websocket_close_reason")
+                    .arg("code", &payload["code"])
+                    .arg("reason", reason)
+            }
+        },
+        Ok(LogKind::PlayerAdd) => Translation::new("log.player_add").arg("channel", channel()),
+        Ok(LogKind::PlayerRemove) => {
+            Translation::new("log.player_remove").arg("channel", channel())
+        },
+        Ok(LogKind::PlayerUpdate) => {
+            let value = &payload["value"];
+            match text("field").parse::<PlayerUpdateInfo>() {
+                Ok(PlayerUpdateInfo::Looping) => {
+                    Translation::new("log.player_update_looping").arg("value", value)
                 },
-                PlayerUpdateInfo::Paused if value.as_bool().unwrap_or_default() => {
-                    "Paused the player.".to_owned()
+                Ok(PlayerUpdateInfo::Playing) => {
+                    Translation::new("log.player_update_playing").arg("track", track())
                 },
-                PlayerUpdateInfo::Paused => "Resumed the player.".to_owned(),
-                PlayerUpdateInfo::Volume => {
-                    format!("Set the player volume to {}%.", value)
+                Ok(PlayerUpdateInfo::Position) => Translation::new("log.player_update_position")
+                    .arg(
+                        "position",
+                        format_duration(value.as_u64().unwrap_or_default()),
+                    ),
+                Ok(PlayerUpdateInfo::Paused) if value.as_bool().unwrap_or_default() => {
+                    Translation::new("log.player_update_paused")
                 },
-                PlayerUpdateInfo::Filters => {
-                    "Updated the player filters or the equalizer.".to_owned()
+                Ok(PlayerUpdateInfo::Paused) => Translation::new("log.player_update_resumed"),
+                Ok(PlayerUpdateInfo::Volume) => {
+                    Translation::new("log.player_update_volume").arg("value", value)
                 },
+                Ok(PlayerUpdateInfo::Filters) => Translation::new("log.player_update_filters"),
+                Err(_) => Translation::new("log.player_update"),
             }
         },
-        _ => "".to_owned(),
+        Ok(LogKind::QueueAdd) => Translation::new("log.queue_add").arg("track", track()),
+        Ok(LogKind::QueueAddFavourites) => {
+            Translation::new("log.queue_add_favourites").arg("amount", &payload["amount"])
+        },
+        Ok(LogKind::QueueRemove) => Translation::new("log.queue_remove").arg("track", track()),
+        Ok(LogKind::QueueShift) => Translation::new("log.queue_shift")
+            .arg("track", track())
+            .arg("position", &payload["position"]),
+        Ok(LogKind::QueueClear) => {
+            Translation::new("log.queue_clear").arg("amount", &payload["amount"])
+        },
+        Ok(LogKind::QueueShuffle) => Translation::new("log.queue_shuffle"),
+        Ok(LogKind::PlaylistLoad) => Translation::new("log.playlist_load")
+            .arg("amount", &payload["amount"])
+            .arg("playlist", text("playlist")),
+        Ok(LogKind::PlaylistAdd) => {
+            Translation::new("log.playlist_add").arg("playlist", text("playlist"))
+        },
+        Ok(LogKind::PlaylistRemove) => {
+            Translation::new("log.playlist_remove").arg("playlist", text("playlist"))
+        },
+        Ok(LogKind::PlaylistUpdate) => Translation::new("log.playlist_update")
+            .arg("playlist", text("playlist"))
+            .arg("changes", format_changes(&payload["changes"])),
+        Ok(LogKind::PlaylistItemAdd) => Translation::new("log.playlist_item_add")
+            .arg("amount", &payload["amount"])
+            .arg("playlist", text("playlist")),
+        Ok(LogKind::PlaylistItemRemove) => Translation::new("log.playlist_item_remove")
+            .arg("playlist", text("playlist"))
+            .arg("track", text("track")),
+        Ok(LogKind::PlaylistItemShift) => Translation::new("log.playlist_item_shift")
+            .arg("position", &payload["position"])
+            .arg("playlist", text("playlist"))
+            .arg("track", text("track")),
+        Ok(LogKind::SettingsUpdate) => Translation::new("log.settings_update")
+            .arg("changes", format_changes(&payload["changes"])),
+        Err(_) => return kind.to_owned(),
     };
 
-    let queue_message = match &info {
-        LogInfo::QueueAdd(track) => {
-            title = "Track Added";
-            format!("Added {} to the queue.", format_track(&track))
+    translation.render(language)
+}
+
+async fn get_entry(
+    redis_pool: &RedisPool,
+    guild: u64,
+    info: &LogInfo,
+) -> ApiResult<Option<(LogKind, Value)>> {
+    let entry = match info {
+        LogInfo::NowPlaying(track) => Some((
+            LogKind::NowPlaying,
+            json!({ "track": track.title, "uri": track.uri, "author": track.author }),
+        )),
+        LogInfo::TrackStuck(error) => Some((
+            LogKind::TrackStuck,
+            json!({ "threshold_ms": error.threshold_ms }),
+        )),
+        LogInfo::TrackException(error) => {
+            Some((LogKind::TrackException, json!({ "error": error.error })))
         },
-        LogInfo::QueueRemove(track) => {
-            title = "Track Removed";
-            format!("Removed {} from the queue.", format_track(&track))
+        LogInfo::WebsocketClose(error) => Some((
+            LogKind::WebsocketClose,
+            json!({ "code": error.code, "reason": error.reason }),
+        )),
+        LogInfo::PlayerAdd(id) => Some((LogKind::PlayerAdd, json!({ "channel": id }))),
+        LogInfo::PlayerRemove(id) => Some((LogKind::PlayerRemove, json!({ "channel": id }))),
+        LogInfo::PlayerUpdate(player) if has_update(player) => {
+            let (key, value) = get_updates(player).remove(0);
+            let mut payload = json!({ "field": key, "value": value });
+
+            if let Ok(PlayerUpdateInfo::Playing) = key.parse() {
+                let track =
+                    queue::get_track(redis_pool, guild, value.as_i64().unwrap_or_default() as i32)
+                        .await?
+                        .unwrap_or_default();
+                payload["track"] = json!(track.title);
+                payload["uri"] = json!(track.uri);
+            }
+
+            Some((LogKind::PlayerUpdate, payload))
         },
-        LogInfo::QueueShift(track, position) => {
-            title = "Track Moved";
-            format!(
-                "Moved {} to position {}.",
-                format_track(&track),
-                position.position
-            )
+        LogInfo::QueueAdd(track) => Some((
+            LogKind::QueueAdd,
+            json!({ "track": track.title, "uri": track.uri }),
+        )),
+        LogInfo::QueueAddFavourites(amount) => {
+            Some((LogKind::QueueAddFavourites, json!({ "amount": amount })))
         },
-        LogInfo::QueueClear(tracks) => {
-            title = "Queue Cleared";
-            format!("Removed {} tracks from the queue.", tracks.len())
+        LogInfo::QueueRemove(track) => Some((
+            LogKind::QueueRemove,
+            json!({ "track": track.title, "uri": track.uri }),
+        )),
+        LogInfo::QueueShift(track, position) => Some((
+            LogKind::QueueShift,
+            json!({ "track": track.title, "uri": track.uri, "position": position.position }),
+        )),
+        LogInfo::QueueClear(tracks) => Some((
+            LogKind::QueueClear,
+            json!({
+                "amount": tracks.len(),
+                "tracks": tracks.iter().map(|track| track.title.as_str()).collect::<Vec<&str>>(),
+            }),
+        )),
+        LogInfo::QueueShuffle => Some((LogKind::QueueShuffle, json!({}))),
+        LogInfo::PlaylistLoad(playlist, amount) => Some((
+            LogKind::PlaylistLoad,
+            json!({ "playlist": playlist.name, "amount": amount }),
+        )),
+        LogInfo::PlaylistAdd(playlist) => {
+            Some((LogKind::PlaylistAdd, json!({ "playlist": playlist.name })))
         },
-        LogInfo::QueueShuffle => {
-            title = "Queue Shuffled";
-            "The queue has been shuffled.".to_owned()
+        LogInfo::PlaylistRemove(playlist) => Some((
+            LogKind::PlaylistRemove,
+            json!({ "playlist": playlist.name }),
+        )),
+        LogInfo::PlaylistUpdate(playlist, new_playlist) => {
+            let changes = get_changes(playlist, new_playlist);
+            if changes.is_empty() {
+                None
+            } else {
+                Some((
+                    LogKind::PlaylistUpdate,
+                    json!({ "playlist": playlist.name, "changes": changes }),
+                ))
+            }
         },
-        LogInfo::PlaylistLoad(playlist, amount) => {
-            title = "Playlist Loaded";
-            format!(
-                "Loaded {} tracks from playlist '{}'.",
-                amount, playlist.name
-            )
+        LogInfo::PlaylistItemAdd(playlist, amount) => Some((
+            LogKind::PlaylistItemAdd,
+            json!({ "playlist": playlist.name, "amount": amount }),
+        )),
+        LogInfo::PlaylistItemRemove(playlist, track) => Some((
+            LogKind::PlaylistItemRemove,
+            json!({ "playlist": playlist.name, "track": track.title }),
+        )),
+        LogInfo::PlaylistItemShift(playlist, track, position) => Some((
+            LogKind::PlaylistItemShift,
+            json!({
+                "playlist": playlist.name,
+                "track": track.title,
+                "position": position.position,
+            }),
+        )),
+        LogInfo::SettingsUpdate(config, new_config) => {
+            let changes = get_changes(config, new_config);
+            if changes.is_empty() {
+                None
+            } else {
+                Some((LogKind::SettingsUpdate, json!({ "changes": changes })))
+            }
         },
-        _ => "".to_owned(),
+        _ => None,
     };
 
-    let action = match &info {
-        LogInfo::PlaylistAdd(playlist) => {
-            format!("Created a playlist ({}).", playlist.name)
-        },
-        LogInfo::PlaylistRemove(playlist) => {
-            format!("Deleted a playlist ({}).", playlist.name)
-        },
-        LogInfo::PlaylistUpdate(playlist) if has_update(&playlist) => {
-            format!(
-                "Updated a playlist ({}).",
-                playlist.name.as_deref().unwrap_or_default()
+    Ok(entry)
+}
+
+async fn get_webhook(pool: &PgPool, guild: u64, kind: LogKind) -> ApiResult<Option<GuildWebhook>> {
+    if let Some(category) = kind.category() {
+        guild_webhook::find(pool, guild as i64, category.as_str().to_owned()).await
+    } else {
+        Ok(None)
+    }
+}
+
+async fn send_entry(
+    pool: &PgPool,
+    redis_pool: &RedisPool,
+    config: &Config,
+    channel: i64,
+    entry: &Option<(LogKind, Value)>,
+    origin: LogOrigin,
+    author: Option<u64>,
+) -> ApiResult<()> {
+    if let Some((kind, payload)) = entry {
+        let language = config.language.as_str();
+        let title = get_title(language, *kind);
+        let message = render(language, kind.as_str(), payload, true);
+        let footer = get_origin(language, origin);
+
+        if let Some(webhook) = get_webhook(pool, config.id as u64, *kind).await? {
+            webhook::deliver(
+                pool,
+                redis_pool,
+                webhook,
+                title.as_str(),
+                message.as_str(),
+                footer.as_str(),
+                author,
+            );
+        } else if channel > 0 {
+            Message::send_message(
+                channel as u64,
+                title.as_str(),
+                message.as_str(),
+                footer.as_str(),
+                author,
             )
-        },
-        LogInfo::SettingsUpdate(settings) if has_update(&settings) => {
-            format!(
-                "Updated the settings ({}).",
-                get_updates(&settings)
-                    .into_iter()
-                    .map(|(k, _)| k)
-                    .collect::<Vec<String>>()
-                    .join(", ")
+            .send(redis_pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn save_entry(
+    pool: &PgPool,
+    config: &Config,
+    guild: u64,
+    author: u64,
+    entry: Option<(LogKind, Value)>,
+    origin: LogOrigin,
+) -> ApiResult<()> {
+    if let Some((kind, payload)) = entry {
+        let keep = match kind.category() {
+            Some(category) => config
+                .log_categories
+                .iter()
+                .any(|item| item == category.as_str()),
+            None => true,
+        };
+
+        if keep {
+            guild_log::create(
+                pool,
+                NewGuildLog {
+                    guild: guild as i64,
+                    author: author as i64,
+                    kind: kind.as_str().to_owned(),
+                    payload,
+                    origin: origin.as_str().to_owned(),
+                },
             )
-        },
-        _ => "".to_owned(),
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn register(
+    pool: &PgPool,
+    redis_pool: &RedisPool,
+    guild: u64,
+    user: User,
+    info: LogInfo,
+) -> ApiResult<()> {
+    let entry = get_entry(redis_pool, guild, &info).await?;
+    let origin = if user.is_bot {
+        LogOrigin::Command
+    } else if user.api_token.is_some() {
+        LogOrigin::Api
+    } else {
+        LogOrigin::Dashboard
     };
 
     let config = cache::get_config(pool, redis_pool, guild).await?;
 
-    if !user.is_bot && config.player_log > 0 && !player_message.is_empty() {
-        Message::send_message(
-            config.player_log as u64,
-            title,
-            player_message.as_str(),
-            user.user.id as u64,
-        )
-        .send(redis_pool)
-        .await?;
-    } else if !user.is_bot && config.queue_log > 0 && !queue_message.is_empty() {
-        Message::send_message(
-            config.queue_log as u64,
-            title,
-            queue_message.as_str(),
-            user.user.id as u64,
-        )
-        .send(redis_pool)
-        .await?;
-    }
+    let channel = match entry.as_ref().and_then(|(kind, _)| kind.category()) {
+        Some(LogCategory::Player) => config.player_log,
+        Some(LogCategory::Queue) => config.queue_log,
+        _ => 0,
+    };
 
-    if !action.is_empty() {
-        guild_log::create(
-            pool,
-            NewGuildLog {
-                guild: guild as i64,
-                action,
-                author: user.user.id as i64,
-            },
-        )
-        .await?;
-    }
+    send_entry(
+        pool,
+        redis_pool,
+        &config,
+        channel,
+        &entry,
+        origin,
+        Some(user.user.id as u64),
+    )
+    .await?;
+
+    save_entry(pool, &config, guild, user.user.id as u64, entry, origin).await?;
 
     Ok(())
 }
@@ -235,50 +568,55 @@ pub async fn register_playing(
     guild: u64,
     info: LogInfo,
 ) -> ApiResult<()> {
-    let mut title = "";
-
-    let message = match info {
-        LogInfo::NowPlaying(track) => {
-            title = "Now Playing";
-            format!("{} - <@{}>", format_track(&track), track.author)
-        },
-        LogInfo::TrackStuck(error) => {
-            title = "Track Stuck";
-            format!("The track got stuck for {}ms.", error.threshold_ms)
-        },
-        LogInfo::TrackException(error) => {
-            title = "Track Exception";
-            format!("The track could not be played: {}.", error.error)
-        },
-        LogInfo::WebsocketClose(error) => {
-            title = "Websocket Closed";
-            format!(
-                "The bot was disconnected: {}{}.",
-                error.code,
-                error
-                    .reason
-                    .map(|mut reason| {
-                        if reason.ends_with('.') {
-                            reason.pop();
-                        }
-                        if !reason.is_empty() {
-                            reason = format!(" {}", reason);
-                        }
-                        reason
-                    })
-                    .unwrap_or_default()
-            )
-        },
-        _ => "".to_owned(),
+    let entry = get_entry(redis_pool, guild, &info).await?;
+    let author = match &info {
+        LogInfo::NowPlaying(track) => track.author as u64,
+        _ => CONFIG.bot_client_id,
     };
 
     let config = cache::get_config(pool, redis_pool, guild).await?;
 
-    if config.playing_log > 0 && !message.is_empty() {
-        Message::send_message(config.playing_log as u64, title, message.as_str(), None)
-            .send(redis_pool)
-            .await?;
-    }
+    send_entry(
+        pool,
+        redis_pool,
+        &config,
+        config.playing_log,
+        &entry,
+        LogOrigin::System,
+        None,
+    )
+    .await?;
+
+    save_entry(pool, &config, guild, author, entry, LogOrigin::System).await?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_csv_plain() {
+        assert_eq!(escape_csv("Added a track"), "Added a track");
+    }
+
+    #[test]
+    fn escape_csv_quotes() {
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("a\nb"), "\"a\nb\"");
+    }
+
+    #[test]
+    fn escape_csv_formulas() {
+        assert_eq!(escape_csv("=1+1"), "'=1+1");
+        assert_eq!(escape_csv("+1"), "'+1");
+        assert_eq!(escape_csv("-1"), "'-1");
+        assert_eq!(escape_csv("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(
+            escape_csv("=HYPERLINK(\"x\",\"y\")"),
+            "\"'=HYPERLINK(\"\"x\"\",\"\"y\"\")\""
+        );
+    }
+}
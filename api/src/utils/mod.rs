@@ -1,24 +1,36 @@
 use crate::routes::ApiResult;
-use crate::utils::queue::QueueItem;
 
 use actix_web::web::block;
 use chrono::Duration;
 use std::thread;
 
 pub mod auth;
+pub mod locale;
 pub mod log;
 pub mod metrics;
 pub mod player;
+pub mod playlist;
 pub mod polling;
 pub mod queue;
+pub mod signature;
+pub mod webhook;
+
+pub fn html_escape(content: &str) -> String {
+    content
+        .replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;")
+        .replace("\"", "&quot;")
+        .replace("'", "&#x27;")
+}
 
 pub fn html_unescape(content: &str) -> String {
     content
         .replace("&lt;", "<")
         .replace("&gt;", ">")
-        .replace("&amp;", "&")
         .replace("&quot;", "\"")
         .replace("&#x27;", "'")
+        .replace("&amp;", "&")
 }
 
 pub fn format_duration(milliseconds: u64) -> String {
@@ -40,10 +52,6 @@ pub fn format_duration(milliseconds: u64) -> String {
     }
 }
 
-pub fn format_track(track: &QueueItem) -> String {
-    format!("[{}]({})", track.title, track.uri)
-}
-
 pub async fn sleep(duration: std::time::Duration) -> ApiResult<()> {
     block(move || -> ApiResult<()> {
         thread::sleep(duration);
@@ -3,7 +3,8 @@ use crate::constants::{player_key, PLAYER_QUEUE, PLAYER_RECONNECT_WAIT, PLAYER_S
 use crate::db::pubsub::models::Connected;
 use crate::db::pubsub::Message;
 use crate::db::{cache, PgPool, RedisPool};
-use crate::models::guild_stat::{self, NewGuildStat};
+use crate::models::guild_stat::{self, EndReason, NewGuildStat};
+use crate::models::history::{self, NewHistory};
 use crate::routes::ApiResult;
 use crate::utils::log::{self, LogInfo};
 use crate::utils::{polling, queue, sleep};
@@ -103,25 +104,73 @@ async fn handle_payload(
                 guild: guild as i64,
                 author: playing.author,
                 title: playing.title.clone(),
+                track: playing.track.clone(),
+                uri: playing.uri.clone(),
+                length: playing.length,
             };
 
             guild_stat::create(pool, stat).await?;
+
+            let connected: Option<Connected> = Message::get_connected(guild, None)
+                .send_and_wait(redis_pool)
+                .await?;
+
+            if let Some(connected) = connected {
+                let bots = connected.bots;
+                let histories: Vec<NewHistory> = connected
+                    .members
+                    .into_iter()
+                    .filter(|member| {
+                        *member != CONFIG.bot_client_id as i64 && !bots.contains(member)
+                    })
+                    .map(|member| NewHistory {
+                        account: member,
+                        guild: guild as i64,
+                        track: playing.track.clone(),
+                        title: playing.title.clone(),
+                        uri: playing.uri.clone(),
+                        length: playing.length,
+                    })
+                    .collect();
+
+                if !histories.is_empty() {
+                    history::batch_create(pool, histories).await?;
+                }
+            }
+
             log::register_playing(pool, redis_pool, guild, LogInfo::NowPlaying(playing)).await?;
 
             polling::notify(guild)?;
         },
         IncomingEvent::TrackEnd(event) => {
+            let reason = match event.reason.as_str() {
+                "FINISHED" => EndReason::Finished,
+                "LOAD_FAILED" => EndReason::Exception,
+                _ => EndReason::Skipped,
+            };
+
+            guild_stat::set_reason(pool, guild as i64, event.track.clone(), reason).await?;
+
             if event.reason == "FINISHED" {
                 queue::play_next(redis_pool, guild).await?;
                 polling::notify(guild)?;
             }
         },
         IncomingEvent::TrackStuck(event) => {
+            guild_stat::set_reason(pool, guild as i64, event.track.clone(), EndReason::Stuck)
+                .await?;
             log::register_playing(pool, redis_pool, guild, LogInfo::TrackStuck(event)).await?;
             queue::play_next(redis_pool, guild).await?;
             polling::notify(guild)?;
         },
         IncomingEvent::TrackException(event) => {
+            guild_stat::set_reason(
+                pool,
+                guild as i64,
+                event.track.clone(),
+                EndReason::Exception,
+            )
+            .await?;
             log::register_playing(pool, redis_pool, guild, LogInfo::TrackException(event)).await?;
             queue::play_next(redis_pool, guild).await?;
             polling::notify(guild)?;
@@ -0,0 +1,366 @@
+use crate::db::PgPool;
+use crate::models::guild_stat;
+use crate::models::playlist::{self, Playlist, PlaylistSummary};
+use crate::models::playlist_item::{self, PlaylistItem};
+use crate::routes::{ApiResponse, ApiResult};
+use crate::utils::player::{decode_track, get_track};
+use crate::utils::{html_escape, html_unescape};
+
+use percent_encoding::{percent_encode, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use twilight_andesite::http::{LoadType, Track};
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlaylistFormat {
+    Json,
+    M3u,
+    Xspf,
+}
+
+impl FromStr for PlaylistFormat {
+    type Err = serde_json::error::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(format!("\"{}\"", s).as_str())
+    }
+}
+
+impl PlaylistFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::M3u => "audio/x-mpegurl",
+            Self::Xspf => "application/xspf+xml",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::M3u => "m3u",
+            Self::Xspf => "xspf",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ExportItem {
+    #[serde(default)]
+    pub track: Option<String>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub uri: Option<String>,
+    #[serde(default)]
+    pub length: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExportPlaylist {
+    pub name: Option<String>,
+    pub items: Vec<ExportItem>,
+}
+
+impl From<PlaylistItem> for ExportItem {
+    fn from(item: PlaylistItem) -> Self {
+        Self {
+            track: Some(item.track),
+            title: Some(item.title),
+            uri: Some(item.uri),
+            length: Some(item.length),
+        }
+    }
+}
+
+pub fn export(format: PlaylistFormat, name: &str, items: Vec<PlaylistItem>) -> ApiResult<String> {
+    let content = match format {
+        PlaylistFormat::Json => serde_json::to_string_pretty(&ExportPlaylist {
+            name: Some(name.to_owned()),
+            items: items.into_iter().map(ExportItem::from).collect(),
+        })?,
+        PlaylistFormat::M3u => {
+            let mut content = format!("#EXTM3U\n#PLAYLIST:{}\n", name);
+            for item in items {
+                content.push_str(
+                    format!(
+                        "#EXTINF:{},{}\n{}\n",
+                        item.length / 1000,
+                        item.title.replace('\n', " "),
+                        item.uri
+                    )
+                    .as_str(),
+                );
+            }
+            content
+        },
+        PlaylistFormat::Xspf => {
+            let mut content = format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" \
+                 xmlns=\"http://xspf.org/ns/0/\">\n  <title>{}</title>\n  <trackList>\n",
+                html_escape(name)
+            );
+            for item in items {
+                content.push_str(
+                    format!(
+                        "    <track>\n      <location>{}</location>\n      \
+                         <title>{}</title>\n      <duration>{}</duration>\n    </track>\n",
+                        html_escape(item.uri.as_str()),
+                        html_escape(item.title.as_str()),
+                        item.length
+                    )
+                    .as_str(),
+                );
+            }
+            content.push_str("  </trackList>\n</playlist>\n");
+            content
+        },
+    };
+
+    Ok(content)
+}
+
+fn get_tag(content: &str, tag: &str) -> Option<String> {
+    let start = content.find(format!("<{}>", tag).as_str())? + tag.len() + 2;
+    let end = content[start..].find(format!("</{}>", tag).as_str())? + start;
+
+    Some(html_unescape(content[start..end].trim()))
+}
+
+pub fn parse(format: PlaylistFormat, content: &str) -> ApiResult<ExportPlaylist> {
+    let playlist = match format {
+        PlaylistFormat::Json => serde_json::from_str(content)
+            .map_err(|_| ApiResponse::bad_request().message("error.playlist_file_invalid"))?,
+        PlaylistFormat::M3u => {
+            let mut name = None;
+            let mut items = vec![];
+            let mut item = ExportItem::default();
+
+            for line in content.lines().map(|line| line.trim()) {
+                if let Some(title) = line.strip_prefix("#PLAYLIST:") {
+                    name = Some(title.trim().to_owned());
+                } else if let Some(info) = line.strip_prefix("#EXTINF:") {
+                    let mut info = info.splitn(2, ',');
+                    item.length = info
+                        .next()
+                        .and_then(|length| length.trim().parse::<i32>().ok())
+                        .filter(|length| *length >= 0)
+                        .map(|length| length * 1000);
+                    item.title = info.next().map(|title| title.trim().to_owned());
+                } else if !line.is_empty() && !line.starts_with('#') {
+                    item.uri = Some(line.to_owned());
+                    items.push(item);
+                    item = ExportItem::default();
+                }
+            }
+
+            ExportPlaylist { name, items }
+        },
+        PlaylistFormat::Xspf => {
+            let track_list = content.find("<trackList>").unwrap_or_default();
+            let name = get_tag(&content[..track_list], "title");
+
+            let items = content[track_list..]
+                .split("<track>")
+                .skip(1)
+                .map(|track| ExportItem {
+                    track: None,
+                    title: get_tag(track, "title"),
+                    uri: get_tag(track, "location"),
+                    length: get_tag(track, "duration").and_then(|length| length.parse().ok()),
+                })
+                .collect();
+
+            ExportPlaylist { name, items }
+        },
+    };
+
+    Ok(playlist)
+}
+
+pub async fn decode_tracks(tracks: &[String]) -> ApiResult<Vec<Track>> {
+    let mut decoded_tracks = vec![];
+
+    for track in tracks {
+        let decoded_track = decode_track(
+            percent_encode(track.as_bytes(), NON_ALPHANUMERIC)
+                .to_string()
+                .as_str(),
+        )
+        .await
+        .map_err(|_| ApiResponse::bad_request().message("error.track_not_found"))?;
+
+        decoded_tracks.push(decoded_track);
+    }
+
+    Ok(decoded_tracks)
+}
+
+pub async fn resolve(item: &ExportItem) -> Option<Track> {
+    if let Some(track) = &item.track {
+        let encoded = percent_encode(track.as_bytes(), NON_ALPHANUMERIC).to_string();
+        if let Ok(track) = decode_track(encoded.as_str()).await {
+            return Some(track);
+        }
+    }
+
+    let mut queries = vec![];
+    queries.extend(item.uri.clone());
+    queries.extend(
+        item.title
+            .as_ref()
+            .map(|title| format!("ytsearch:{}", title)),
+    );
+
+    for query in queries {
+        if let Ok(tracks) = get_track(query.as_str()).await {
+            if tracks.load_type == LoadType::LoadFailed || tracks.load_type == LoadType::NoMatches {
+                continue;
+            }
+
+            if let Some(track) = tracks.tracks.and_then(|tracks| tracks.into_iter().next()) {
+                return Some(track);
+            }
+        }
+    }
+
+    None
+}
+
+pub async fn get_items(pool: &PgPool, playlist: &Playlist) -> ApiResult<Vec<PlaylistItem>> {
+    if !playlist.smart {
+        return playlist_item::find_by_playlist(pool, playlist.id).await;
+    }
+
+    let items = guild_stat::find_top_tracks(
+        pool,
+        playlist.guild.unwrap_or_default(),
+        playlist.smart_author,
+        playlist.smart_days,
+        playlist.smart_amount as i64,
+    )
+    .await?
+    .into_iter()
+    .enumerate()
+    .map(|(index, track)| PlaylistItem {
+        id: 0,
+        playlist: playlist.id,
+        track: track.track,
+        title: track.title,
+        uri: track.uri,
+        length: track.length,
+        position: index as i32,
+    })
+    .collect();
+
+    Ok(items)
+}
+
+pub async fn get_summaries(pool: &PgPool, guild: i64) -> ApiResult<Vec<PlaylistSummary>> {
+    let mut summaries = playlist::find_summary_by_guild(pool, guild).await?;
+
+    for summary in summaries.iter_mut().filter(|summary| summary.smart) {
+        let tracks = guild_stat::find_top_tracks(
+            pool,
+            guild,
+            summary.smart_author,
+            summary.smart_days,
+            summary.smart_amount as i64,
+        )
+        .await?;
+
+        summary.items = tracks.len() as i64;
+        summary.length = tracks.iter().map(|track| track.length as i64).sum();
+    }
+
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_item(title: &str, uri: &str, length: i32) -> PlaylistItem {
+        PlaylistItem {
+            id: 0,
+            playlist: 0,
+            track: String::new(),
+            title: title.to_owned(),
+            uri: uri.to_owned(),
+            length,
+            position: 0,
+        }
+    }
+
+    #[test]
+    fn export_m3u() {
+        let items = vec![get_item("First\nTrack", "https://example.com/1", 61500)];
+        let content = export(PlaylistFormat::M3u, "Mix", items).unwrap();
+
+        assert_eq!(
+            content,
+            "#EXTM3U\n#PLAYLIST:Mix\n#EXTINF:61,First Track\nhttps://example.com/1\n"
+        );
+    }
+
+    #[test]
+    fn parse_m3u() {
+        let content = "#EXTM3U\n#PLAYLIST: Mix \n#EXTINF:61, First, Track\r\n\
+                       https://example.com/1\n\n# comment\nhttps://example.com/2\n\
+                       #EXTINF:-1,Stream\nhttps://example.com/3\n";
+        let playlist = parse(PlaylistFormat::M3u, content).unwrap();
+
+        assert_eq!(playlist.name.as_deref(), Some("Mix"));
+        assert_eq!(playlist.items.len(), 3);
+        assert_eq!(playlist.items[0].title.as_deref(), Some("First, Track"));
+        assert_eq!(
+            playlist.items[0].uri.as_deref(),
+            Some("https://example.com/1")
+        );
+        assert_eq!(playlist.items[0].length, Some(61000));
+        assert_eq!(playlist.items[1].title, None);
+        assert_eq!(playlist.items[1].length, None);
+        assert_eq!(playlist.items[2].length, None);
+    }
+
+    #[test]
+    fn export_xspf() {
+        let items = vec![get_item(
+            "<Tom & Jerry>",
+            "https://example.com/?a=1&b=2",
+            1000,
+        )];
+        let content = export(PlaylistFormat::Xspf, "\"Mix\"", items).unwrap();
+
+        assert!(content.contains("<title>&quot;Mix&quot;</title>"));
+        assert!(content.contains("<location>https://example.com/?a=1&amp;b=2</location>"));
+        assert!(content.contains("<title>&lt;Tom &amp; Jerry&gt;</title>"));
+        assert!(content.contains("<duration>1000</duration>"));
+    }
+
+    #[test]
+    fn parse_xspf() {
+        let items = vec![
+            get_item("<Tom & Jerry>", "https://example.com/1", 1000),
+            get_item("&quot; literal", "https://example.com/2", 2000),
+        ];
+        let content = export(PlaylistFormat::Xspf, "Mix", items).unwrap();
+        let playlist = parse(PlaylistFormat::Xspf, content.as_str()).unwrap();
+
+        assert_eq!(playlist.name.as_deref(), Some("Mix"));
+        assert_eq!(playlist.items.len(), 2);
+        assert_eq!(playlist.items[0].title.as_deref(), Some("<Tom & Jerry>"));
+        assert_eq!(
+            playlist.items[0].uri.as_deref(),
+            Some("https://example.com/1")
+        );
+        assert_eq!(playlist.items[0].length, Some(1000));
+        assert_eq!(playlist.items[1].title.as_deref(), Some("&quot; literal"));
+    }
+
+    #[test]
+    fn parse_invalid_json() {
+        assert!(parse(PlaylistFormat::Json, "{").is_err());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::config::CONFIG;
+use crate::constants::{
+    bot_nonce_key, BOT_NONCE_KEY_TTL, PAYLOAD_MAX, SIGNATURE_NONCE_MAX, SIGNATURE_WINDOW,
+};
+use crate::db::{cache, RedisPool};
+use crate::routes::{ApiError, ApiResponse, ApiResult, OptionExt, ResultExt};
+
+use actix_web::body::MessageBody;
+use actix_web::dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::error::PayloadError;
+use actix_web::http::HeaderMap;
+use actix_web::web::BytesMut;
+use actix_web::{HttpMessage, HttpRequest};
+use chrono::Utc;
+use futures::future::{ok, Ready};
+use futures::{stream, Future, StreamExt};
+use hmac::{Hmac, Mac, NewMac};
+use http::header::{AUTHORIZATION, CONTENT_LENGTH};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+pub const SIGNATURE_PREFIX: &str = "Signature ";
+
+#[derive(Debug, Clone)]
+pub struct BodyDigest(pub String);
+
+pub fn is_signed(headers: &HeaderMap) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with(SIGNATURE_PREFIX))
+        .unwrap_or(false)
+}
+
+pub fn get_header<'a>(headers: &'a HeaderMap, name: &str) -> ApiResult<&'a str> {
+    let value = headers
+        .get(name)
+        .map(|value| value.to_str())
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(value)
+}
+
+pub fn get_message(
+    method: &str,
+    path: &str,
+    timestamp: &str,
+    nonce: &str,
+    user: [&str; 4],
+    digest: &str,
+) -> String {
+    let mut parts = vec![method, path, timestamp, nonce];
+    parts.extend_from_slice(&user);
+    parts.push(digest);
+
+    parts.join("\n")
+}
+
+pub async fn verify(pool: &RedisPool, req: &HttpRequest, signature: &str) -> ApiResult<()> {
+    let headers = req.headers();
+
+    let timestamp = get_header(headers, "X-Timestamp")?;
+    let nonce = get_header(headers, "X-Nonce")?;
+    let digest = req
+        .extensions()
+        .get::<BodyDigest>()
+        .map(|digest| digest.0.clone())
+        .or_unauthorized()?;
+
+    let age = Utc::now().timestamp() - timestamp.parse::<i64>().or_unauthorized()?;
+    if age.abs() > SIGNATURE_WINDOW || nonce.is_empty() || nonce.len() > SIGNATURE_NONCE_MAX {
+        return Err(ApiResponse::unauthorized().into());
+    }
+
+    let path = req
+        .uri()
+        .path_and_query()
+        .map(|path| path.as_str())
+        .unwrap_or_default();
+
+    let message = get_message(
+        req.method().as_str(),
+        path,
+        timestamp,
+        nonce,
+        [
+            get_header(headers, "User-Id")?,
+            get_header(headers, "User-Username")?,
+            get_header(headers, "User-Discriminator")?,
+            get_header(headers, "User-Avatar")?,
+        ],
+        digest.as_str(),
+    );
+
+    let mut mac =
+        Hmac::<Sha256>::new_varkey(CONFIG.bot_signing_secret.as_bytes()).or_unauthorized()?;
+    mac.update(message.as_bytes());
+    mac.verify(hex::decode(signature).or_unauthorized()?.as_slice())
+        .or_unauthorized()?;
+
+    let key = bot_nonce_key(nonce);
+    if !cache::set_nx_and_expire(pool, key, &timestamp, BOT_NONCE_KEY_TTL).await? {
+        return Err(ApiResponse::unauthorized().into());
+    }
+
+    Ok(())
+}
+
+pub struct Signature;
+
+impl<S, B> Transform<S> for Signature
+where
+    B: MessageBody,
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Transform = SignatureMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(SignatureMiddleware {
+            service: Rc::new(RefCell::new(service)),
+        })
+    }
+}
+
+pub struct SignatureMiddleware<S> {
+    service: Rc<RefCell<S>>,
+}
+
+impl<S, B> Service for SignatureMiddleware<S>
+where
+    B: MessageBody,
+    S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = actix_web::Error>
+        + 'static,
+{
+    type Request = ServiceRequest;
+    type Response = ServiceResponse<B>;
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.borrow_mut().poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: ServiceRequest) -> Self::Future {
+        let service = self.service.clone();
+
+        Box::pin(async move {
+            if is_signed(req.headers()) {
+                let length = req
+                    .headers()
+                    .get(CONTENT_LENGTH)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .unwrap_or_default();
+
+                if length > PAYLOAD_MAX {
+                    return Err(ApiError::from(ApiResponse::payload_too_large()).into());
+                }
+
+                let mut body = BytesMut::new();
+                let mut payload = req.take_payload();
+                while let Some(chunk) = payload.next().await {
+                    let chunk = chunk?;
+                    if body.len() + chunk.len() > PAYLOAD_MAX {
+                        return Err(ApiError::from(ApiResponse::payload_too_large()).into());
+                    }
+
+                    body.extend_from_slice(&chunk);
+                }
+
+                let body = body.freeze();
+                let digest = format!("{:x}", Sha256::digest(&body));
+
+                req.extensions_mut().insert(BodyDigest(digest));
+                req.set_payload(Payload::Stream(Box::pin(stream::once(
+                    ok::<_, PayloadError>(body),
+                ))));
+            }
+
+            let fut = service.borrow_mut().call(req);
+            fut.await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn message_joins_parts() {
+        let message = get_message("GET", "/guilds/1", "1", "a", ["2", "b", "0001", ""], "d");
+
+        assert_eq!(message, "GET\n/guilds/1\n1\na\n2\nb\n0001\n\nd");
+    }
+
+    #[test]
+    fn message_signature() {
+        let digest = format!("{:x}", Sha256::digest(b"{}"));
+        let message = get_message(
+            "POST",
+            "/guilds/1/player?x=1",
+            "1609459200",
+            "abc",
+            ["2", "name", "0001", ""],
+            digest.as_str(),
+        );
+
+        let mut mac = Hmac::<Sha256>::new_varkey(b"secret").unwrap();
+        mac.update(message.as_bytes());
+
+        assert_eq!(
+            digest,
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a"
+        );
+        assert_eq!(
+            hex::encode(mac.finalize().into_bytes()),
+            "680720b86dc3e810224a6d96eaa9a6ee692dea31fceae18bc6e6457d5e5038fb"
+        );
+    }
+}
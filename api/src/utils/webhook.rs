@@ -0,0 +1,184 @@
+use crate::constants::{webhook_ratelimit_key, EMBED_COLOR, WEBHOOK_RETRY_MAX, WEBHOOK_RETRY_WAIT};
+use crate::db::{cache, PgPool, RedisPool};
+use crate::models::account;
+use crate::models::guild_webhook::{self, GuildWebhook};
+use crate::routes::ApiResult;
+use crate::utils::sleep;
+
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::warn;
+
+fn get_header(headers: &HeaderMap, name: &str) -> Option<f64> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<f64>().ok())
+}
+
+async fn wait_ratelimit(redis_pool: &RedisPool, key: &str) -> ApiResult<()> {
+    let reset: Option<i64> = cache::get(redis_pool, key).await?;
+
+    if let Some(reset) = reset {
+        let remaining = reset - Utc::now().timestamp_millis();
+        if remaining > 0 {
+            sleep(Duration::from_millis(remaining as u64)).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn set_ratelimit(redis_pool: &RedisPool, key: &str, reset_after: u64) -> ApiResult<()> {
+    let reset = Utc::now().timestamp_millis() + reset_after as i64;
+
+    cache::set_and_expire(redis_pool, key, &reset, reset_after as usize + 1000).await?;
+
+    Ok(())
+}
+
+async fn send(redis_pool: &RedisPool, webhook: &GuildWebhook, body: &Value) -> Result<(), String> {
+    let key = webhook_ratelimit_key(webhook.guild as u64, webhook.category.as_str());
+    let client = reqwest::Client::new();
+    let mut error = String::new();
+
+    for attempt in 0..WEBHOOK_RETRY_MAX {
+        wait_ratelimit(redis_pool, key.as_str())
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let response = client
+            .post(format!("{}?wait=true", webhook.url).as_str())
+            .json(body)
+            .send()
+            .await;
+
+        let backoff = (WEBHOOK_RETRY_WAIT << attempt) as u64;
+
+        match response {
+            Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                let retry_after = get_header(response.headers(), "retry-after").unwrap_or(1.0);
+                set_ratelimit(redis_pool, key.as_str(), (retry_after * 1000.0) as u64)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                error = "Rate limited by Discord.".to_owned();
+                continue;
+            },
+            Ok(response) => {
+                let headers = response.headers();
+                if get_header(headers, "x-ratelimit-remaining") == Some(0.0) {
+                    if let Some(reset_after) = get_header(headers, "x-ratelimit-reset-after") {
+                        set_ratelimit(redis_pool, key.as_str(), (reset_after * 1000.0) as u64)
+                            .await
+                            .map_err(|err| err.to_string())?;
+                    }
+                }
+
+                let status = response.status();
+                if status.is_success() {
+                    return Ok(());
+                }
+
+                error = format!(
+                    "Discord responded with {}: {}",
+                    status,
+                    response.text().await.unwrap_or_default()
+                );
+
+                if status.is_client_error() {
+                    return Err(error);
+                }
+            },
+            Err(err) => {
+                error = format!("The request failed: {}", err);
+            },
+        }
+
+        sleep(Duration::from_millis(backoff))
+            .await
+            .map_err(|err| err.to_string())?;
+    }
+
+    Err(error)
+}
+
+async fn get_embed(
+    pool: &PgPool,
+    title: &str,
+    content: &str,
+    footer: &str,
+    author: Option<u64>,
+) -> Value {
+    let mut embed = json!({
+        "title": title,
+        "description": content,
+        "color": EMBED_COLOR,
+    });
+
+    if !footer.is_empty() {
+        embed["footer"] = json!({ "text": footer });
+    }
+
+    if let Some(author) = author {
+        if let Ok(Some(account)) = account::find(pool, author as i64).await {
+            embed["author"] = json!({
+                "name": format!("{}#{:04}", account.username, account.discriminator),
+            });
+
+            if !account.avatar.is_empty() {
+                embed["author"]["icon_url"] = json!(format!(
+                    "https://cdn.discordapp.com/avatars/{}/{}.png",
+                    account.id, account.avatar
+                ));
+            }
+        }
+    }
+
+    embed
+}
+
+pub fn deliver(
+    pool: &PgPool,
+    redis_pool: &RedisPool,
+    webhook: GuildWebhook,
+    title: &str,
+    content: &str,
+    footer: &str,
+    author: impl Into<Option<u64>>,
+) {
+    let pool = pool.clone();
+    let redis_pool = redis_pool.clone();
+    let title = title.to_owned();
+    let content = content.to_owned();
+    let footer = footer.to_owned();
+    let author = author.into();
+
+    actix_web::rt::spawn(async move {
+        let embed = get_embed(
+            &pool,
+            title.as_str(),
+            content.as_str(),
+            footer.as_str(),
+            author,
+        )
+        .await;
+        let body = json!({ "embeds": [embed] });
+
+        let res = match send(&redis_pool, &webhook, &body).await {
+            Ok(()) => {
+                guild_webhook::set_delivered(&pool, webhook.guild, webhook.category.clone()).await
+            },
+            Err(error) => {
+                guild_webhook::set_failed(&pool, webhook.guild, webhook.category.clone(), error)
+                    .await
+            },
+        };
+
+        if let Err(err) = res {
+            warn!("Failed to update webhook status: {:?}", err);
+        }
+    });
+}
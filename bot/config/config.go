@@ -27,9 +27,9 @@ var (
 	BotAdminRole  *Option
 	GuildsChannel *Option
 
-	ApiHost   *Option
-	ApiPort   *Option
-	ApiSecret *Option
+	ApiHost          *Option
+	ApiPort          *Option
+	BotSigningSecret *Option
 
 	RedisHost *Option
 	RedisPort *Option
@@ -64,7 +64,7 @@ func Init() {
 
 	ApiHost = register("api_host", true)
 	ApiPort = register("api_port", true)
-	ApiSecret = register("api_secret", true)
+	BotSigningSecret = register("bot_signing_secret", true)
 
 	RedisHost = register("redis_host", true)
 	RedisPort = register("redis_port", true)
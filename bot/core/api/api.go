@@ -2,9 +2,16 @@ package api
 
 import (
 	"bytes"
+	"crypto/hmac"
+	"crypto/rand"
+	"crypto/sha256"
+	"encoding/hex"
 	"encoding/json"
 	"io/ioutil"
 	"net/http"
+	"strconv"
+	"strings"
+	"time"
 
 	"github.com/chamburr/wyvor/config"
 	"github.com/chamburr/wyvor/utils/logger"
@@ -41,6 +48,38 @@ func (apiErr ApiError) Error() string {
 	return apiErr.Message()
 }
 
+func signRequest(req *http.Request, body []byte) error {
+	nonce := make([]byte, 16)
+	_, err := rand.Read(nonce)
+	if err != nil {
+		return err
+	}
+
+	bodyHash := sha256.Sum256(body)
+	timestamp := strconv.FormatInt(time.Now().Unix(), 10)
+
+	message := strings.Join([]string{
+		req.Method,
+		req.URL.RequestURI(),
+		timestamp,
+		hex.EncodeToString(nonce),
+		req.Header.Get("User-Id"),
+		req.Header.Get("User-Username"),
+		req.Header.Get("User-Discriminator"),
+		req.Header.Get("User-Avatar"),
+		hex.EncodeToString(bodyHash[:]),
+	}, "\n")
+
+	mac := hmac.New(sha256.New, []byte(config.BotSigningSecret.GetString()))
+	mac.Write([]byte(message))
+
+	req.Header.Set("Authorization", "Signature "+hex.EncodeToString(mac.Sum(nil)))
+	req.Header.Set("X-Timestamp", timestamp)
+	req.Header.Set("X-Nonce", hex.EncodeToString(nonce))
+
+	return nil
+}
+
 func RequestGet(user *discordgo.User, url string) (result map[string]interface{}, err error) {
 	res, err := Request(user, "GET", url, nil)
 	if err != nil {
@@ -112,13 +151,17 @@ func Request(user *discordgo.User, method string, url string, data interface{})
 	}
 
 	req.Header.Set("Content-Type", "application/json")
-	req.Header.Set("Authorization", config.ApiSecret.GetString())
 
 	req.Header.Set("User-Id", discordgo.StrID(user.ID))
 	req.Header.Set("User-Username", user.Username)
 	req.Header.Set("User-Discriminator", user.Discriminator)
 	req.Header.Set("User-Avatar", user.Avatar)
 
+	err = signRequest(req, buffer)
+	if err != nil {
+		return
+	}
+
 	req.Close = true
 
 	resp, err := client.Do(req)
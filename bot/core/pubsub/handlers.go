@@ -181,6 +181,10 @@ func HandleSendMessage(event *EventData) (err error) {
 		embed.Title = data.Title
 		embed.Description = data.Content
 
+		if data.Footer != "" {
+			embed.Footer = &discordgo.MessageEmbedFooter{Text: data.Footer}
+		}
+
 		if guild := common.State.Guild(true, channel.Guild.ID); guild != nil {
 			if author := guild.Member(true, data.Author); author != nil {
 				embed.Author = &discordgo.MessageEmbedAuthor{
@@ -208,16 +212,21 @@ func HandleGetConnected(event *EventData) (err error) {
 		}
 
 		if state := guild.VoiceState(true, data.Member); state != nil {
-			var members []int64
+			members, bots := []int64{}, []int64{}
 			for _, voice := range guild.Guild.VoiceStates {
 				if voice.ChannelID == state.ChannelID {
 					members = append(members, voice.UserID)
+
+					if member := guild.Member(true, voice.UserID); member != nil && member.Bot {
+						bots = append(bots, voice.UserID)
+					}
 				}
 			}
 
 			return event.Respond(RespondGetConnected{
 				Channel: state.ChannelID,
 				Members: members,
+				Bots:    bots,
 			})
 		}
 	}
@@ -38,6 +38,7 @@ type ReceiveSendMessage struct {
 	Channel int64  `json:"channel"`
 	Title   string `json:"title"`
 	Content string `json:"content"`
+	Footer  string `json:"footer"`
 	Author  int64  `json:"author"`
 }
 
@@ -86,6 +87,7 @@ type RespondGetGuild struct {
 type RespondGetConnected struct {
 	Channel int64   `json:"channel"`
 	Members []int64 `json:"members"`
+	Bots    []int64 `json:"bots"`
 }
 
 type SendVoiceUpdate struct {